@@ -0,0 +1,209 @@
+use crate::{
+    error::UniProtHeaderError, filter::trim_newline, header::FastaHeader, parser::is_accession,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    io::BufRead,
+};
+
+/// Current status of an accession
+#[derive(Debug, PartialEq, Clone)]
+pub enum Resolution<'a> {
+    /// Neither secondary nor deleted, the accession is its own primary accession
+    Primary(&'a str),
+    /// Secondary accession, with its primary accessions (several when the entry was split)
+    Secondary(&'a [String]),
+    /// Deleted accession
+    Deleted,
+}
+
+impl<'a> Resolution<'a> {
+    /// Current primary accessions, empty for deleted accessions
+    pub fn primaries(&self) -> Vec<&'a str> {
+        match self {
+            Resolution::Primary(accession) => vec![accession],
+            Resolution::Secondary(primaries) => primaries.iter().map(String::as_str).collect(),
+            Resolution::Deleted => Vec::new(),
+        }
+    }
+}
+
+/// Map old accessions to the current ones with UniProt's `sec_ac.txt` and `delac_sp.txt`/`delac_tr.txt`
+///
+/// ```rust
+/// use uniprot_fasta_header::{AccessionResolver, Resolution};
+///
+/// let mut resolver = AccessionResolver::new();
+/// resolver.read_sec_ac(&b"Secondary AC  Primary AC\n____________  __________\nP29358        P02668\n"[..]).unwrap();
+/// resolver.read_delac(&b"Q8MHS1\n"[..]).unwrap();
+///
+/// assert_eq!(resolver.resolve("P29358").primaries(), vec!["P02668"]);
+/// assert_eq!(resolver.resolve("Q8MHS1"), Resolution::Deleted);
+///
+/// let header = uniprot_fasta_header::uniprotkb(b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1").unwrap();
+/// assert!(resolver.matches("P29358", &header));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct AccessionResolver {
+    secondary: HashMap<String, Vec<String>>,
+    deleted: HashSet<String>,
+}
+
+// Lines made of accessions only, the text around the lists is skipped
+fn accession_lines<R: BufRead, F>(mut reader: R, mut f: F) -> Result<usize, UniProtHeaderError>
+where
+    F: FnMut(Vec<&str>) -> bool,
+{
+    let mut buffer = Vec::new();
+    let mut counter = 0;
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            return Ok(counter);
+        }
+        let line = String::from_utf8_lossy(trim_newline(&buffer));
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if !words.is_empty() && words.iter().all(|w| is_accession(w.as_bytes())) && f(words) {
+            counter += 1;
+        }
+    }
+}
+
+impl AccessionResolver {
+    /// Empty resolver
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load `sec_ac.txt`, returns the number of secondary to primary pairs
+    pub fn read_sec_ac<R: BufRead>(&mut self, reader: R) -> Result<usize, UniProtHeaderError> {
+        let secondary = &mut self.secondary;
+        accession_lines(reader, |words| match words.as_slice() {
+            [secondary_ac, primary_ac] => {
+                let primaries = secondary.entry(secondary_ac.to_string()).or_default();
+                if !primaries.iter().any(|p| p == primary_ac) {
+                    primaries.push(primary_ac.to_string());
+                }
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Load `delac_sp.txt` or `delac_tr.txt`, returns the number of deleted accessions
+    pub fn read_delac<R: BufRead>(&mut self, reader: R) -> Result<usize, UniProtHeaderError> {
+        let deleted = &mut self.deleted;
+        accession_lines(reader, |words| match words.as_slice() {
+            [accession] => {
+                deleted.insert(accession.to_string());
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Status of an accession
+    pub fn resolve<'a>(&'a self, accession: &'a str) -> Resolution<'a> {
+        if let Some(primaries) = self.secondary.get(accession) {
+            Resolution::Secondary(primaries)
+        } else if self.deleted.contains(accession) {
+            Resolution::Deleted
+        } else {
+            Resolution::Primary(accession)
+        }
+    }
+
+    /// Whether an old accession now belongs to a parsed header, isoforms match their canonical accession
+    pub fn matches<H: FastaHeader + ?Sized>(&self, accession: &str, header: &H) -> bool {
+        let identifier = header.identifier();
+        let canonical = identifier
+            .split_once('-')
+            .map_or(identifier.as_ref(), |(canonical, _)| canonical);
+        self.resolve(accession).primaries().contains(&canonical)
+    }
+
+    /// Number of secondary accessions
+    pub fn secondary_len(&self) -> usize {
+        self.secondary.len()
+    }
+
+    /// Number of deleted accessions
+    pub fn deleted_len(&self) -> usize {
+        self.deleted.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SEC_AC: &str = "\
+----------------------------------------------------------------------------
+        UniProt - Swiss-Prot and TrEMBL Protein Knowledgebase
+        List of secondary accession numbers and their primary counterparts
+----------------------------------------------------------------------------
+Release:     2020_04 of 12-Aug-2020
+Number of entries: 3
+
+Secondary AC  Primary AC
+____________  __________
+A0A021WW64    A0A0B4K6F6
+P29358        P02668
+Q9UMT2        P12345
+Q9UMT2        P12346
+";
+
+    const DELAC: &str = "\
+        Deleted accessions: 2
+_______
+A0A009E0D9
+Q8MHS1
+
+-----------------------------------------------------------------------
+UniProt is copyrighted by the UniProt Consortium, see https://www.uniprot.org/terms
+";
+
+    fn resolver() -> AccessionResolver {
+        let mut resolver = AccessionResolver::new();
+        assert_eq!(resolver.read_sec_ac(SEC_AC.as_bytes()).unwrap(), 4);
+        assert_eq!(resolver.read_delac(DELAC.as_bytes()).unwrap(), 2);
+        resolver
+    }
+
+    #[test]
+    fn resolve() {
+        let resolver = resolver();
+        assert_eq!(resolver.secondary_len(), 3);
+        assert_eq!(resolver.deleted_len(), 2);
+        assert_eq!(
+            resolver.resolve("A0A021WW64"),
+            Resolution::Secondary(&["A0A0B4K6F6".to_string()])
+        );
+        assert_eq!(
+            resolver.resolve("Q9UMT2").primaries(),
+            vec!["P12345", "P12346"]
+        );
+        assert_eq!(resolver.resolve("A0A009E0D9"), Resolution::Deleted);
+        assert_eq!(resolver.resolve("P02668"), Resolution::Primary("P02668"));
+        assert!(resolver.resolve("Q8MHS1").primaries().is_empty());
+    }
+
+    #[test]
+    fn accessions() {
+        assert!(is_accession(b"P02668"));
+        assert!(is_accession(b"A0A0B4K6F6"));
+        assert!(!is_accession(b"Release:"));
+        assert!(!is_accession(b"xP02668"));
+        assert!(!is_accession(b"P02668x"));
+    }
+
+    #[test]
+    fn matches_isoform() {
+        let resolver = resolver();
+        let header = crate::uniprotkb_iso(b">sp|P12345-2|AATM_RABIT Isoform 2 of Aspartate aminotransferase, mitochondrial OS=Oryctolagus cuniculus OX=9986 GN=GOT2").unwrap();
+        assert!(resolver.matches("Q9UMT2", &header));
+        assert!(resolver.matches("P12345", &header));
+        assert!(!resolver.matches("A0A021WW64", &header));
+    }
+}
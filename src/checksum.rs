@@ -0,0 +1,249 @@
+use crate::{
+    error::UniProtHeaderError,
+    fasta::{FastaReader, Record},
+    flat_file::EntryReader,
+    format::parse_any,
+    header::FastaHeader,
+};
+use std::{collections::HashMap, io::BufRead};
+
+// Reversed ISO 3309 polynomial used by SWISS-PROT
+const POLY64REV: u64 = 0xD800_0000_0000_0000;
+
+const fn crc64_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut part = i as u64;
+        let mut j = 0;
+        while j < 8 {
+            part = if part & 1 == 1 {
+                (part >> 1) ^ POLY64REV
+            } else {
+                part >> 1
+            };
+            j += 1;
+        }
+        table[i] = part;
+        i += 1;
+    }
+    table
+}
+
+static CRC64_TABLE: [u64; 256] = crc64_table();
+
+/// CRC64 of a sequence as in the `SQ` line of UniProt entries, 16 uppercase hexadecimal digits
+///
+/// ```rust
+/// assert_eq!(uniprot_fasta_header::crc64(b"ACGTACGTACGT"), "C4FBB762C4A87EBD");
+/// ```
+pub fn crc64(sequence: &[u8]) -> String {
+    let crc = sequence.iter().fold(0u64, |crc, &c| {
+        CRC64_TABLE[((crc ^ u64::from(c)) & 0xFF) as usize] ^ (crc >> 8)
+    });
+    format!("{:016X}", crc)
+}
+
+/// MD5 of a sequence, 32 uppercase hexadecimal digits
+pub fn md5(sequence: &[u8]) -> String {
+    format!("{:X}", md5::compute(sequence))
+}
+
+impl Record {
+    /// CRC64 of the sequence
+    pub fn crc64(&self) -> String {
+        crc64(self.sequence.as_bytes())
+    }
+
+    /// MD5 of the sequence
+    pub fn md5(&self) -> String {
+        md5(self.sequence.as_bytes())
+    }
+}
+
+/// Comparison of a sequence with a recorded checksum
+#[derive(Debug, PartialEq, Clone)]
+pub enum ChecksumCheck {
+    /// Same checksum
+    Match,
+    /// Different checksums, the sequence is corrupted or belongs to another version of the entry
+    Mismatch {
+        /// Recorded checksum
+        expected: String,
+        /// Checksum of the sequence
+        computed: String,
+    },
+    /// No recorded checksum for the accession
+    Unknown,
+}
+
+/// Compare the CRC64 of a sequence with a recorded one, case-insensitive
+pub fn check_crc64(sequence: &[u8], expected: &str) -> ChecksumCheck {
+    let computed = crc64(sequence);
+    if computed.eq_ignore_ascii_case(expected) {
+        ChecksumCheck::Match
+    } else {
+        ChecksumCheck::Mismatch {
+            expected: expected.to_string(),
+            computed,
+        }
+    }
+}
+
+/// CRC64 checksums of flat file entries, to validate the sequences of FASTA files
+///
+/// ```rust
+/// use uniprot_fasta_header::{ChecksumCheck, ChecksumValidator};
+///
+/// let dat: &[u8] = b"ID   TEST_HUMAN              Reviewed;         12 AA.
+/// AC   P99999;
+/// DT   01-JAN-2000, sequence version 1.
+/// DE   RecName: Full=Test protein;
+/// OS   Homo sapiens (Human).
+/// OX   NCBI_TaxID=9606;
+/// PE   1: Evidence at protein level;
+/// SQ   SEQUENCE   12 AA;  1300 MW;  C4FBB762C4A87EBD CRC64;
+///      ACGTACGTAC GT
+/// //
+/// ";
+/// let fasta: &[u8] = b">sp|P99999|TEST_HUMAN Test protein OS=Homo sapiens OX=9606 PE=1 SV=1\nACGTACGTACGA\n";
+///
+/// let mut validator = ChecksumValidator::new();
+/// validator.read_dat(dat).unwrap();
+///
+/// let invalid = validator.validate_fasta(fasta).unwrap();
+/// assert_eq!(invalid[0].0, "P99999");
+/// assert!(matches!(invalid[0].1, ChecksumCheck::Mismatch { .. }));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ChecksumValidator {
+    checksums: HashMap<String, String>,
+}
+
+impl ChecksumValidator {
+    /// Empty validator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the CRC64 of an accession
+    pub fn insert(&mut self, accession: String, crc64: String) {
+        self.checksums.insert(accession, crc64);
+    }
+
+    /// Load the CRC64 of the entries of a flat file (`.dat`), returns the number of checksums
+    pub fn read_dat<R: BufRead>(&mut self, reader: R) -> Result<usize, UniProtHeaderError> {
+        let mut counter = 0;
+        for entry in EntryReader::new(reader) {
+            let mut entry = entry?;
+            if let Some(crc64) = entry.crc64.take() {
+                self.insert(entry.accessions.swap_remove(0), crc64);
+                counter += 1;
+            }
+        }
+        Ok(counter)
+    }
+
+    /// Compare a sequence with the checksum of an accession
+    pub fn check_sequence(&self, accession: &str, sequence: &str) -> ChecksumCheck {
+        match self.checksums.get(accession) {
+            Some(expected) => check_crc64(sequence.as_bytes(), expected),
+            None => ChecksumCheck::Unknown,
+        }
+    }
+
+    /// Compare the sequence of a record with the checksum of its accession
+    pub fn check(&self, record: &Record) -> Result<ChecksumCheck, UniProtHeaderError> {
+        let header = parse_any(record.header.as_bytes())?;
+        Ok(self.check_sequence(&header.identifier(), &record.sequence))
+    }
+
+    /// Accessions of the records of a FASTA file whose sequence does not match, or without checksum
+    pub fn validate_fasta<R: BufRead>(
+        &self,
+        reader: R,
+    ) -> Result<Vec<(String, ChecksumCheck)>, UniProtHeaderError> {
+        let mut invalid = Vec::new();
+        for record in FastaReader::new(reader) {
+            let record = record?;
+            let header = parse_any(record.header.as_bytes())?;
+            let accession = header.identifier();
+            match self.check_sequence(&accession, &record.sequence) {
+                ChecksumCheck::Match => (),
+                check => invalid.push((accession.to_string(), check)),
+            }
+        }
+        Ok(invalid)
+    }
+
+    /// Number of checksums
+    pub fn len(&self) -> usize {
+        self.checksums.len()
+    }
+
+    /// No checksums
+    pub fn is_empty(&self) -> bool {
+        self.checksums.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case(b"", "0000000000000000" ; "empty")]
+    #[test_case(b"ACGTACGTACGT", "C4FBB762C4A87EBD" ; "biopython")]
+    #[test_case(b"MMKSFFLVVTILALTLPFLGAQEQNQEQPIRCEKDERFFSDKIAKYIPIQYVLSRYPSYGLNYYQQKPVA", "B26F7F1E0156904D" ; "protein")]
+    fn crc64_values(sequence: &[u8], expected: &str) {
+        pretty_assertions::assert_eq!(crc64(sequence), expected);
+    }
+
+    #[test]
+    fn md5_value() {
+        assert_eq!(md5(b"ACGTACGTACGT"), "31E91BECCF6059FF57C696827C0C6A4B");
+        let record = Record {
+            header: ">a".to_string(),
+            sequence: "ACGTACGTACGT".to_string(),
+        };
+        assert_eq!(record.md5(), "31E91BECCF6059FF57C696827C0C6A4B");
+        assert_eq!(record.crc64(), "C4FBB762C4A87EBD");
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(
+            check_crc64(b"ACGTACGTACGT", "c4fbb762c4a87ebd"),
+            ChecksumCheck::Match
+        );
+        assert_eq!(
+            check_crc64(b"ACGTACGTACGA", "C4FBB762C4A87EBD"),
+            ChecksumCheck::Mismatch {
+                expected: "C4FBB762C4A87EBD".to_string(),
+                computed: crc64(b"ACGTACGTACGA"),
+            }
+        );
+    }
+
+    #[test]
+    fn validate_fasta() {
+        let mut validator = ChecksumValidator::new();
+        validator.insert("P02668".to_string(), "B26F7F1E0156904D".to_string());
+        validator.insert("P12345".to_string(), "C4FBB762C4A87EBD".to_string());
+        assert_eq!(validator.len(), 2);
+
+        let fasta = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1
+MMKSFFLVVTILALTLPFLGAQEQNQEQPIRCEKDERFFSDKIAKYIPIQYVLSRYPSYG
+LNYYQQKPVA
+>sp|P12345|AATM_RABIT Aspartate aminotransferase OS=Oryctolagus cuniculus OX=9986 GN=GOT2 PE=1 SV=2
+ACGTACGTACGA
+>sp|P04637|P53_HUMAN Cellular tumor antigen p53 OS=Homo sapiens OX=9606 GN=TP53 PE=1 SV=4
+MEEPQ
+";
+        let invalid = validator.validate_fasta(&fasta[..]).unwrap();
+        assert_eq!(invalid.len(), 2);
+        assert_eq!(invalid[0].0, "P12345");
+        assert_eq!(invalid[1], ("P04637".to_string(), ChecksumCheck::Unknown));
+    }
+}
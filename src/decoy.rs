@@ -0,0 +1,433 @@
+use crate::{
+    error::UniProtHeaderError,
+    fasta::{FastaReader, Record, DEFAULT_LINE_WIDTH},
+    uniprotkb, uniprotkb_iso,
+};
+use std::io::{BufRead, Write};
+
+/// Decoy and contaminant prefixes of search engine databases
+///
+/// Prefixes are matched right after the `>`, longest first, and can be combined (`>CON__rev_sp|...`).
+#[derive(Debug, PartialEq, Clone)]
+pub struct TagPrefixes {
+    /// Decoy prefixes (`rev_`, `DECOY_`, `XXX_`, ...)
+    pub decoy: Vec<String>,
+    /// Contaminant prefixes (`CON__`, ...)
+    pub contaminant: Vec<String>,
+}
+
+impl Default for TagPrefixes {
+    fn default() -> Self {
+        Self {
+            decoy: ["rev_", "REV_", "REV__", "DECOY_", "decoy_", "XXX_", "xxx_"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            contaminant: ["CON__", "CON_", "CONT_", "contam_"]
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Header with its decoy and contaminant tags
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tagged<T> {
+    /// Header without the prefixes
+    pub header: T,
+    /// A decoy prefix was found
+    pub is_decoy: bool,
+    /// A contaminant prefix was found
+    pub is_contaminant: bool,
+}
+
+impl<T> Tagged<T> {
+    /// Apply a function to the header, keeping the tags
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Tagged<U> {
+        Tagged {
+            header: f(self.header),
+            is_decoy: self.is_decoy,
+            is_contaminant: self.is_contaminant,
+        }
+    }
+}
+
+fn longest_prefix<'a>(header: &[u8], prefixes: &'a [String]) -> Option<&'a String> {
+    prefixes
+        .iter()
+        .filter(|p| !p.is_empty() && header.starts_with(p.as_bytes()))
+        .max_by_key(|p| p.len())
+}
+
+impl TagPrefixes {
+    /// Remove the prefixes of a header line, the returned header still starts with `>`
+    pub fn strip(&self, header: &[u8]) -> Tagged<Vec<u8>> {
+        let mut rest = header.strip_prefix(b">").unwrap_or(header);
+        let mut is_decoy = false;
+        let mut is_contaminant = false;
+
+        loop {
+            if let Some(prefix) = longest_prefix(rest, &self.decoy) {
+                is_decoy = true;
+                rest = &rest[prefix.len()..];
+            } else if let Some(prefix) = longest_prefix(rest, &self.contaminant) {
+                is_contaminant = true;
+                rest = &rest[prefix.len()..];
+            } else {
+                break;
+            }
+        }
+
+        Tagged {
+            header: [&b">"[..], rest].concat(),
+            is_decoy,
+            is_contaminant,
+        }
+    }
+
+    /// Remove the prefixes and parse the underlying header
+    ///
+    /// ```rust
+    /// use uniprot_fasta_header::{uniprotkb, TagPrefixes};
+    ///
+    /// let prefixes = TagPrefixes::default();
+    /// let tagged = prefixes
+    ///     .parse(b">rev_sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1", uniprotkb)
+    ///     .unwrap();
+    ///
+    /// assert!(tagged.is_decoy);
+    /// assert!(!tagged.is_contaminant);
+    /// assert_eq!(tagged.header.identifier, "P02668");
+    /// ```
+    pub fn parse<T, F>(&self, header: &[u8], parser: F) -> Result<Tagged<T>, UniProtHeaderError>
+    where
+        F: Fn(&[u8]) -> Result<T, UniProtHeaderError>,
+    {
+        let stripped = self.strip(header);
+        let parsed = parser(&stripped.header)?;
+        Ok(stripped.map(|_| parsed))
+    }
+}
+
+/// How decoy sequences are made from target sequences
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum DecoyMethod {
+    /// Reversed sequence
+    Reverse,
+    /// Randomly shuffled sequence, reproducible for a given seed
+    Shuffle {
+        /// Seed of the random generator
+        seed: u64,
+    },
+    /// Tryptic peptides reversed in place, the cleavage sites (K or R not followed by P) stay where they are
+    ///
+    /// Decoy peptides keep the mass and the C-terminal residue of their target peptides.
+    PseudoReverse,
+}
+
+// SplitMix64, good enough to shuffle residues and keeps the crate free of a random dependency
+#[derive(Debug, Clone)]
+pub(crate) struct Rng(u64);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Fisher-Yates
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+// Reverse each tryptic peptide, keeping its C-terminal cleavage site
+fn pseudo_reverse(residues: &[char]) -> Vec<char> {
+    let mut decoy = Vec::with_capacity(residues.len());
+    let mut start = 0;
+
+    for (i, &residue) in residues.iter().enumerate() {
+        let cleaved = (residue == 'K' || residue == 'R') && residues.get(i + 1) != Some(&'P');
+        if cleaved {
+            decoy.extend(residues[start..i].iter().rev());
+            decoy.push(residue);
+            start = i + 1;
+        }
+    }
+    decoy.extend(residues[start..].iter().rev());
+
+    decoy
+}
+
+/// Target-decoy database generator
+///
+/// Headers are parsed as UniProtKB or UniProtKB isoform headers and written back with the decoy prefix,
+/// so that decoys can be read again with [`TagPrefixes`].
+///
+/// ```rust
+/// use uniprot_fasta_header::{DecoyGenerator, DecoyMethod};
+///
+/// let fasta: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKSFLLVVTILALTLPFLGAQEQNQEQPIR\n";
+/// let mut database = Vec::new();
+///
+/// let mut generator = DecoyGenerator::new(DecoyMethod::PseudoReverse, "DECOY_");
+/// generator.generate(fasta, &mut database, true).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(database).unwrap(),
+///     ">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKSFLLVVTILALTLPFLGAQEQNQEQPIR\n\
+///      >DECOY_sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKIPQEQNQEQAGLFPLTLALITVVLLFSR\n"
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct DecoyGenerator {
+    method: DecoyMethod,
+    prefix: String,
+    rng: Rng,
+    line_width: usize,
+}
+
+impl DecoyGenerator {
+    /// New generator, `prefix` is added at the start of the decoy headers
+    pub fn new(method: DecoyMethod, prefix: &str) -> Self {
+        let seed = match method {
+            DecoyMethod::Shuffle { seed } => seed,
+            _ => 0,
+        };
+        Self {
+            method,
+            prefix: prefix.to_string(),
+            rng: Rng::new(seed),
+            line_width: DEFAULT_LINE_WIDTH,
+        }
+    }
+
+    /// Wrap the sequences every `line_width` characters (no wrapping if 0)
+    pub fn line_width(mut self, line_width: usize) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    /// Decoy of a target sequence
+    pub fn decoy_sequence(&mut self, sequence: &str) -> String {
+        let mut residues: Vec<char> = sequence.chars().collect();
+        match self.method {
+            DecoyMethod::Reverse => residues.reverse(),
+            DecoyMethod::Shuffle { .. } => self.rng.shuffle(&mut residues),
+            DecoyMethod::PseudoReverse => residues = pseudo_reverse(&residues),
+        }
+        residues.into_iter().collect()
+    }
+
+    /// Decoy of a target record, the header must be a UniProtKB or UniProtKB isoform header
+    pub fn decoy_record(&mut self, record: &Record) -> Result<Record, UniProtHeaderError> {
+        let header = match uniprotkb(record.header.as_bytes()) {
+            Ok(parsed) => parsed.to_string(),
+            Err(e) => match uniprotkb_iso(record.header.as_bytes()) {
+                Ok(parsed) => parsed.to_string(),
+                Err(_) => return Err(e),
+            },
+        };
+
+        Ok(Record {
+            header: format!(">{}{}", self.prefix, &header[1..]),
+            sequence: self.decoy_sequence(&record.sequence),
+        })
+    }
+
+    /// Write the decoy records of a FASTA file, each one after its target if `include_targets` is set
+    ///
+    /// Returns the number of decoys written.
+    pub fn generate<R: BufRead, W: Write>(
+        &mut self,
+        reader: R,
+        mut writer: W,
+        include_targets: bool,
+    ) -> Result<usize, UniProtHeaderError> {
+        let mut counter: usize = 0;
+
+        for record in FastaReader::new(reader) {
+            let record = record?;
+            let decoy = self.decoy_record(&record)?;
+            if include_targets {
+                record.write(&mut writer, self.line_width)?;
+            }
+            decoy.write(&mut writer, self.line_width)?;
+            counter += 1;
+        }
+
+        writer.flush()?;
+        Ok(counter)
+    }
+}
+
+/// Write a decoy FASTA file: one decoy record, with a prefixed header, for each target record
+///
+/// Headers are only prefixed, not parsed: any FASTA file can be used. See [`DecoyGenerator`] to rewrite UniProtKB headers
+/// and to interleave targets and decoys.
+/// Returns the number of decoys written.
+///
+/// ```rust
+/// use uniprot_fasta_header::{write_decoys, DecoyMethod};
+///
+/// let fasta: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKSFLLVVT\n";
+/// let mut decoys = Vec::new();
+///
+/// write_decoys(fasta, &mut decoys, DecoyMethod::Reverse, "rev_").unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(decoys).unwrap(),
+///     ">rev_sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nTVVLLFSKMM\n"
+/// );
+/// ```
+pub fn write_decoys<R: BufRead, W: Write>(
+    reader: R,
+    mut writer: W,
+    method: DecoyMethod,
+    prefix: &str,
+) -> Result<usize, UniProtHeaderError> {
+    let mut generator = DecoyGenerator::new(method, prefix);
+    let mut counter: usize = 0;
+
+    for record in FastaReader::new(reader) {
+        let mut record = record?;
+        record.sequence = generator.decoy_sequence(&record.sequence);
+        record.header = format!(">{}{}", prefix, &record.header[1..]);
+        record.write(&mut writer, generator.line_width)?;
+        counter += 1;
+    }
+
+    writer.flush()?;
+    Ok(counter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uniprotkb, uniprotkb_iso};
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case(b">rev_sp|P02668|CASK_BOVIN", true, false ; "rev")]
+    #[test_case(b">DECOY_tr|P02668|CASK_BOVIN", true, false ; "DECOY")]
+    #[test_case(b">XXX_sp|P02668|CASK_BOVIN", true, false ; "XXX")]
+    #[test_case(b">REV__sp|P02668|CASK_BOVIN", true, false ; "MaxQuant decoy")]
+    #[test_case(b">CON__P02768", false, true ; "contaminant")]
+    #[test_case(b">CON__rev_sp|P02668|CASK_BOVIN", true, true ; "both")]
+    #[test_case(b">sp|P02668|CASK_BOVIN", false, false ; "none")]
+    fn tags(header: &[u8], decoy: bool, contaminant: bool) {
+        let tagged = TagPrefixes::default().strip(header);
+        pretty_assertions::assert_eq!(
+            (tagged.is_decoy, tagged.is_contaminant),
+            (decoy, contaminant)
+        );
+        pretty_assertions::assert_eq!(tagged.header[0], b'>');
+    }
+
+    #[test]
+    fn strip() {
+        assert_eq!(
+            TagPrefixes::default().strip(b">CON__P02768").header,
+            b">P02768".to_vec()
+        );
+    }
+
+    #[test]
+    fn custom_prefixes() {
+        let prefixes = TagPrefixes {
+            decoy: vec!["shuffled_".to_string()],
+            contaminant: vec![],
+        };
+        let tagged = prefixes
+            .parse(b">shuffled_sp|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2", uniprotkb_iso)
+            .unwrap();
+        assert!(tagged.is_decoy);
+        assert_eq!(tagged.header.isoform, "2");
+    }
+
+    #[test]
+    fn shuffle_is_reproducible() {
+        let fasta: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKSFLLVVTILALTLPFLGAQEQNQEQPIRCEKDERFFSDKIAKYIPIQYVLSRYPSYG\n";
+        let mut first = Vec::new();
+        let mut second = Vec::new();
+        write_decoys(fasta, &mut first, DecoyMethod::Shuffle { seed: 42 }, "XXX_").unwrap();
+        write_decoys(
+            fasta,
+            &mut second,
+            DecoyMethod::Shuffle { seed: 42 },
+            "XXX_",
+        )
+        .unwrap();
+        assert_eq!(first, second);
+
+        let record = FastaReader::new(&first[..]).next().unwrap().unwrap();
+        let mut residues: Vec<char> = record.sequence.chars().collect();
+        residues.sort_unstable();
+        let mut expected: Vec<char> =
+            "MMKSFLLVVTILALTLPFLGAQEQNQEQPIRCEKDERFFSDKIAKYIPIQYVLSRYPSYG"
+                .chars()
+                .collect();
+        expected.sort_unstable();
+        assert_eq!(residues, expected);
+
+        let tagged = TagPrefixes::default()
+            .parse(record.header.as_bytes(), uniprotkb)
+            .unwrap();
+        assert!(tagged.is_decoy);
+    }
+
+    #[test_case("MKPAKR", "APKMKR" ; "no cleavage before proline")]
+    #[test_case("ABKCDERFG", "BAKEDCRGF" ; "two sites")]
+    #[test_case("ABC", "CBA" ; "no site")]
+    #[test_case("", "" ; "empty")]
+    fn pseudo_reversed(target: &str, expected: &str) {
+        let mut generator = DecoyGenerator::new(DecoyMethod::PseudoReverse, "rev_");
+        pretty_assertions::assert_eq!(generator.decoy_sequence(target), expected);
+    }
+
+    #[test]
+    fn round_trip() {
+        let fasta: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKSFLLVVT\n>sp|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2\nMAEEQGR\n";
+        let mut database = Vec::new();
+        let mut generator = DecoyGenerator::new(DecoyMethod::Shuffle { seed: 7 }, "decoy-");
+        assert_eq!(generator.generate(fasta, &mut database, false).unwrap(), 2);
+
+        let prefixes = TagPrefixes {
+            decoy: vec!["decoy-".to_string()],
+            contaminant: vec![],
+        };
+        let records: Vec<Record> = FastaReader::new(&database[..])
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(records.len(), 2);
+
+        let decoy = prefixes
+            .parse(records[0].header.as_bytes(), uniprotkb)
+            .unwrap();
+        assert!(decoy.is_decoy);
+        assert_eq!(decoy.header.identifier, "P02668");
+
+        let decoy = prefixes
+            .parse(records[1].header.as_bytes(), uniprotkb_iso)
+            .unwrap();
+        assert_eq!(decoy.header.isoform, "2");
+    }
+
+    #[test]
+    fn unparsable_header() {
+        let fasta: &[u8] = b">custom\nMMKSFLLVVT\n";
+        let mut generator = DecoyGenerator::new(DecoyMethod::Reverse, "rev_");
+        assert!(generator.generate(fasta, Vec::new(), true).is_err());
+    }
+}
@@ -0,0 +1,349 @@
+use crate::{error::UniProtHeaderError, fasta::FastaReader, uniprotkb, uniprotkb_iso};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{BufRead, Write},
+};
+
+/// Kind of change between two releases
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ChangeKind {
+    /// Accession only in the new release
+    Added,
+    /// Accession only in the old release
+    Removed,
+    /// Sequence version (`SV=`) changed
+    SequenceVersion,
+    /// Protein existence (`PE=`) changed
+    ProteinExistence,
+    /// Protein name changed
+    ProteinName,
+    /// Gene name (`GN=`) changed, added or removed
+    GeneName,
+    /// Entry name changed
+    EntryName,
+}
+
+impl ChangeKind {
+    /// Name used in the TSV report
+    pub fn name(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "added",
+            ChangeKind::Removed => "removed",
+            ChangeKind::SequenceVersion => "sequence_version",
+            ChangeKind::ProteinExistence => "protein_existence",
+            ChangeKind::ProteinName => "protein_name",
+            ChangeKind::GeneName => "gene_name",
+            ChangeKind::EntryName => "entry_name",
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Change of an accession between two releases
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Change {
+    /// Accession, with the isoform number for isoforms
+    pub accession: String,
+    /// Kind of change
+    pub kind: ChangeKind,
+    /// Value in the old release, the header for removed accessions
+    pub old: Option<String>,
+    /// Value in the new release, the header for added accessions
+    pub new: Option<String>,
+}
+
+/// Changes between two releases of a FASTA file
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReleaseDiff {
+    /// Changes, sorted by accession
+    pub changes: Vec<Change>,
+    /// Number of entries of the old release
+    pub old_entries: usize,
+    /// Number of entries of the new release
+    pub new_entries: usize,
+}
+
+impl ReleaseDiff {
+    /// Changes of one kind
+    pub fn of_kind(&self, kind: ChangeKind) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(move |change| change.kind == kind)
+    }
+
+    /// Number of changes of one kind
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.of_kind(kind).count()
+    }
+
+    /// No changes
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Write the changes as a tab separated table: accession, change, old and new values
+    pub fn write_tsv<W: Write>(&self, mut writer: W) -> Result<(), UniProtHeaderError> {
+        writeln!(writer, "accession\tchange\told\tnew")?;
+        for change in &self.changes {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}",
+                change.accession,
+                change.kind,
+                change.old.as_deref().unwrap_or_default(),
+                change.new.as_deref().unwrap_or_default()
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+// Compared fields of a header, isoform headers have no protein existence and sequence version
+#[derive(Debug)]
+struct Entry {
+    header: String,
+    entry_name: String,
+    protein_name: String,
+    gene_name: Option<String>,
+    protein_existence: Option<u8>,
+    sequence_version: Option<String>,
+}
+
+fn read_release<R: BufRead>(reader: R) -> Result<BTreeMap<String, Entry>, UniProtHeaderError> {
+    let mut entries = BTreeMap::new();
+    for record in FastaReader::new(reader) {
+        let header = record?.header;
+        let (accession, entry) = match uniprotkb(header.as_bytes()) {
+            Ok(parsed) => (
+                parsed.identifier,
+                Entry {
+                    header: header.clone(),
+                    entry_name: parsed.entry_name,
+                    protein_name: parsed.protein_name,
+                    gene_name: parsed.gene_name,
+                    protein_existence: Some(parsed.protein_existence.level()),
+                    sequence_version: Some(parsed.sequence_version),
+                },
+            ),
+            Err(_) => {
+                let parsed = uniprotkb_iso(header.as_bytes())?;
+                (
+                    format!("{}-{}", parsed.identifier, parsed.isoform),
+                    Entry {
+                        header: header.clone(),
+                        entry_name: parsed.entry_name,
+                        protein_name: parsed.protein_name,
+                        gene_name: parsed.gene_name,
+                        protein_existence: None,
+                        sequence_version: None,
+                    },
+                )
+            }
+        };
+        if entries.contains_key(&accession) {
+            return Err(UniProtHeaderError::DuplicateHeader(accession));
+        }
+        entries.insert(accession, entry);
+    }
+    Ok(entries)
+}
+
+fn compare(accession: &str, old: &Entry, new: &Entry, changes: &mut Vec<Change>) {
+    let mut push = |kind, old: Option<String>, new: Option<String>| {
+        if old != new {
+            changes.push(Change {
+                accession: accession.to_string(),
+                kind,
+                old,
+                new,
+            });
+        }
+    };
+    push(
+        ChangeKind::SequenceVersion,
+        old.sequence_version.clone(),
+        new.sequence_version.clone(),
+    );
+    push(
+        ChangeKind::ProteinExistence,
+        old.protein_existence.map(|pe| pe.to_string()),
+        new.protein_existence.map(|pe| pe.to_string()),
+    );
+    push(
+        ChangeKind::ProteinName,
+        Some(old.protein_name.clone()),
+        Some(new.protein_name.clone()),
+    );
+    push(
+        ChangeKind::GeneName,
+        old.gene_name.clone(),
+        new.gene_name.clone(),
+    );
+    push(
+        ChangeKind::EntryName,
+        Some(old.entry_name.clone()),
+        Some(new.entry_name.clone()),
+    );
+}
+
+/// Compare two releases of a UniProtKB FASTA file (canonical and isoform headers)
+///
+/// Fails on headers that are not UniProtKB headers and on accessions found twice in a release.
+///
+/// ```rust
+/// use uniprot_fasta_header::{diff_fasta, ChangeKind};
+///
+/// let old: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=2 SV=1\nMMKSF\n";
+/// let new: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=2\nMMKSFL\n";
+///
+/// let diff = diff_fasta(old, new).unwrap();
+///
+/// assert_eq!(diff.count(ChangeKind::SequenceVersion), 1);
+/// assert_eq!(diff.count(ChangeKind::ProteinExistence), 1);
+/// assert_eq!(diff.count(ChangeKind::Added), 0);
+/// ```
+pub fn diff_fasta<R1: BufRead, R2: BufRead>(
+    old: R1,
+    new: R2,
+) -> Result<ReleaseDiff, UniProtHeaderError> {
+    let old = read_release(old)?;
+    let new = read_release(new)?;
+
+    let mut changes = Vec::new();
+    for (accession, old_entry) in &old {
+        match new.get(accession) {
+            Some(new_entry) => compare(accession, old_entry, new_entry, &mut changes),
+            None => changes.push(Change {
+                accession: accession.clone(),
+                kind: ChangeKind::Removed,
+                old: Some(old_entry.header.clone()),
+                new: None,
+            }),
+        }
+    }
+    for (accession, new_entry) in &new {
+        if !old.contains_key(accession) {
+            changes.push(Change {
+                accession: accession.clone(),
+                kind: ChangeKind::Added,
+                old: None,
+                new: Some(new_entry.header.clone()),
+            });
+        }
+    }
+    changes.sort_by(|a, b| (&a.accession, a.kind).cmp(&(&b.accession, b.kind)));
+
+    Ok(ReleaseDiff {
+        changes,
+        old_entries: old.len(),
+        new_entries: new.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const OLD: &str = "\
+>sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1
+MMKSF
+>sp|P12345|AATM_RABIT Aspartate aminotransferase OS=Oryctolagus cuniculus OX=9986 GN=GOT2 PE=2 SV=1
+MALLH
+>tr|A0A023GPI8|A0A023GPI8_CANAL Lectin OS=Canavalia lineata OX=28957 PE=4 SV=1
+MAISK
+>sp|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2
+MAEEQ
+";
+
+    const NEW: &str = "\
+>sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1
+MMKSF
+>sp|P12345|AATM2_RABIT Aspartate aminotransferase, mitochondrial OS=Oryctolagus cuniculus OX=9986 PE=1 SV=2
+MALLHS
+>sp|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2
+MAEEQ
+>sp|P04637|P53_HUMAN Cellular tumor antigen p53 OS=Homo sapiens OX=9606 GN=TP53 PE=1 SV=4
+MEEPQ
+";
+
+    fn change(accession: &str, kind: ChangeKind, old: Option<&str>, new: Option<&str>) -> Change {
+        Change {
+            accession: accession.to_string(),
+            kind,
+            old: old.map(String::from),
+            new: new.map(String::from),
+        }
+    }
+
+    #[test]
+    fn diff() {
+        let diff = diff_fasta(OLD.as_bytes(), NEW.as_bytes()).unwrap();
+        assert_eq!(diff.old_entries, 4);
+        assert_eq!(diff.new_entries, 4);
+        assert_eq!(
+            diff.changes,
+            vec![
+                change(
+                    "A0A023GPI8",
+                    ChangeKind::Removed,
+                    Some(">tr|A0A023GPI8|A0A023GPI8_CANAL Lectin OS=Canavalia lineata OX=28957 PE=4 SV=1"),
+                    None
+                ),
+                change(
+                    "P04637",
+                    ChangeKind::Added,
+                    None,
+                    Some(">sp|P04637|P53_HUMAN Cellular tumor antigen p53 OS=Homo sapiens OX=9606 GN=TP53 PE=1 SV=4")
+                ),
+                change("P12345", ChangeKind::SequenceVersion, Some("1"), Some("2")),
+                change("P12345", ChangeKind::ProteinExistence, Some("2"), Some("1")),
+                change(
+                    "P12345",
+                    ChangeKind::ProteinName,
+                    Some("Aspartate aminotransferase"),
+                    Some("Aspartate aminotransferase, mitochondrial")
+                ),
+                change("P12345", ChangeKind::GeneName, Some("GOT2"), None),
+                change("P12345", ChangeKind::EntryName, Some("AATM_RABIT"), Some("AATM2_RABIT")),
+            ]
+        );
+    }
+
+    #[test]
+    fn same_release() {
+        let diff = diff_fasta(OLD.as_bytes(), OLD.as_bytes()).unwrap();
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn tsv() {
+        let diff = diff_fasta(OLD.as_bytes(), NEW.as_bytes()).unwrap();
+        let mut tsv = Vec::new();
+        diff.write_tsv(&mut tsv).unwrap();
+        let tsv = String::from_utf8(tsv).unwrap();
+        let lines: Vec<&str> = tsv.lines().collect();
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], "accession\tchange\told\tnew");
+        assert_eq!(lines[6], "P12345\tgene_name\tGOT2\t");
+    }
+
+    #[test]
+    fn duplicate_accession() {
+        let fasta = format!("{}{}", OLD, OLD);
+        assert_eq!(
+            diff_fasta(fasta.as_bytes(), NEW.as_bytes()).unwrap_err(),
+            UniProtHeaderError::DuplicateHeader("P02668".to_string())
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use crate::{
+    error::UniProtHeaderError,
+    filter::{Field, Filterable},
+    header::FastaHeader,
+    parser::{chevron, ensembl_key_value, ensembl_source, genomic_location, space, versioned_id},
+};
+use nom::{
+    bytes::complete::take_till1,
+    combinator::all_consuming,
+    error::{ErrorKind, ParseError},
+    multi::many0,
+    sequence::preceded,
+    IResult,
+};
+use std::{borrow::Cow, fmt};
+
+/// Ensembl stable identifier, with its version
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StableId {
+    /// Identifier without version (`ENSP00000269305`)
+    pub id: String,
+    /// Version (`4` in `ENSP00000269305.4`)
+    pub version: Option<String>,
+}
+
+impl fmt::Display for StableId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.version {
+            Some(version) => write!(f, "{}.{}", self.id, version),
+            None => write!(f, "{}", self.id),
+        }
+    }
+}
+
+/// Genomic location of an Ensembl peptide
+///
+/// `chromosome:GRCh38:17:7661779:7687538:-1`
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GenomicLocation {
+    /// Coordinate system (`chromosome`, `scaffold`, `primary_assembly`, ...)
+    pub coord_system: String,
+    /// Assembly (`GRCh38`)
+    pub assembly: String,
+    /// Sequence region name (`17`)
+    pub seq_region: String,
+    /// Start position, 1-based
+    pub start: u64,
+    /// End position, inclusive
+    pub end: u64,
+    /// Strand, `1` or `-1`
+    pub strand: i8,
+}
+
+impl fmt::Display for GenomicLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}:{}:{}:{}",
+            self.coord_system, self.assembly, self.seq_region, self.start, self.end, self.strand
+        )
+    }
+}
+
+/// Source of an Ensembl description
+///
+/// `[Source:HGNC Symbol;Acc:HGNC:11998]`
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DescriptionSource {
+    /// Source name (`HGNC Symbol`, `UniProtKB/Swiss-Prot`, ...)
+    pub name: String,
+    /// Accession in the source (`HGNC:11998`)
+    pub accession: String,
+}
+
+/// Ensembl peptide header (`pep.all.fa`)
+///
+/// `>ProteinID pep Location gene:GeneID transcript:TranscriptID gene_biotype:Biotype transcript_biotype:Biotype gene_symbol:Symbol description:Description [Source:Name;Acc:Accession]`
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ensembl {
+    /// Protein stable identifier
+    pub protein: StableId,
+    /// Sequence type and status, `pep` (`pep:known` in older releases)
+    pub status: String,
+    /// Genomic location
+    pub location: GenomicLocation,
+    /// Gene stable identifier
+    pub gene: StableId,
+    /// Transcript stable identifier
+    pub transcript: StableId,
+    /// Gene biotype
+    pub gene_biotype: Option<String>,
+    /// Transcript biotype
+    pub transcript_biotype: Option<String>,
+    /// Gene symbol
+    pub gene_symbol: Option<String>,
+    /// Description, without the source
+    pub description: Option<String>,
+    /// Source of the description
+    pub description_source: Option<DescriptionSource>,
+}
+
+/// Write the Ensembl fasta header
+impl fmt::Display for Ensembl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ">{} {} {} gene:{} transcript:{}",
+            self.protein, self.status, self.location, self.gene, self.transcript
+        )?;
+        if let Some(gene_biotype) = &self.gene_biotype {
+            write!(f, " gene_biotype:{}", gene_biotype)?;
+        }
+        if let Some(transcript_biotype) = &self.transcript_biotype {
+            write!(f, " transcript_biotype:{}", transcript_biotype)?;
+        }
+        if let Some(gene_symbol) = &self.gene_symbol {
+            write!(f, " gene_symbol:{}", gene_symbol)?;
+        }
+        if self.description.is_some() || self.description_source.is_some() {
+            write!(
+                f,
+                " description:{}",
+                self.description.as_deref().unwrap_or("")
+            )?;
+        }
+        if let Some(source) = &self.description_source {
+            if self.description.is_some() {
+                write!(f, " ")?;
+            }
+            write!(f, "[Source:{};Acc:{}]", source.name, source.accession)?;
+        }
+        Ok(())
+    }
+}
+
+impl Filterable for Ensembl {
+    fn field(&self, field: Field) -> Option<Cow<'_, str>> {
+        match field {
+            Field::Database => Some(Cow::Borrowed("ensembl")),
+            Field::Accession => Some(Cow::Borrowed(&self.protein.id)),
+            Field::ProteinName => self.description.as_deref().map(Cow::Borrowed),
+            Field::GeneName => self.gene_symbol.as_deref().map(Cow::Borrowed),
+            Field::SequenceVersion => self.protein.version.as_deref().map(Cow::Borrowed),
+            _ => None,
+        }
+    }
+}
+
+impl FastaHeader for Ensembl {
+    fn identifier(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.protein.id)
+    }
+
+    fn organism_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn taxon_id(&self) -> Option<&str> {
+        None
+    }
+
+    fn gene_name(&self) -> Option<&str> {
+        self.gene_symbol.as_deref()
+    }
+
+    fn description(&self) -> &str {
+        self.description.as_deref().unwrap_or("")
+    }
+
+    fn source_database(&self) -> &'static str {
+        "ensembl"
+    }
+}
+
+/// Parse an Ensembl peptide fasta header
+///
+/// ```rust
+/// let header = b">ENSP00000269305.4 pep chromosome:GRCh38:17:7661779:7687538:-1 gene:ENSG00000141510.18 transcript:ENST00000269305.9 gene_biotype:protein_coding transcript_biotype:protein_coding gene_symbol:TP53 description:tumor protein p53 [Source:HGNC Symbol;Acc:HGNC:11998]";
+///
+/// let parsed = uniprot_fasta_header::ensembl(header).unwrap();
+///
+/// assert_eq!(parsed.protein.to_string(), "ENSP00000269305.4");
+/// assert_eq!(parsed.location.seq_region, "17");
+/// assert_eq!(parsed.gene.id, "ENSG00000141510");
+/// assert_eq!(parsed.description, Some("tumor protein p53".to_string()));
+/// assert_eq!(parsed.description_source.unwrap().accession, "HGNC:11998");
+/// ```
+pub fn ensembl(string: &[u8]) -> Result<Ensembl, UniProtHeaderError> {
+    match parse_ensembl(string) {
+        Ok((_, parsed)) => Ok(parsed),
+        Err(err) => match err {
+            nom::Err::Incomplete(_i) => Err(UniProtHeaderError::Incomplete),
+            nom::Err::Error((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+            nom::Err::Failure((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+        },
+    }
+}
+
+fn to_string(input: &[u8]) -> String {
+    String::from_utf8_lossy(input).to_string()
+}
+
+fn stable_id(input: &[u8]) -> Result<StableId, nom::Err<(&[u8], ErrorKind)>> {
+    let (_, (id, version)) = all_consuming(versioned_id)(input)?;
+    Ok(StableId {
+        id: to_string(id),
+        version: version.map(to_string),
+    })
+}
+
+fn parse_ensembl(input: &[u8]) -> IResult<&[u8], Ensembl> {
+    let (input, _) = chevron(input)?;
+    let (input, (id, version)) = versioned_id(input)?;
+    let (input, status) = preceded(space, take_till1(|c| c == b' '))(input)?;
+    let (input, (coord_system, assembly, seq_region, start, end, strand)) =
+        preceded(space, genomic_location)(input)?;
+    let (input, key_values) = all_consuming(many0(preceded(space, ensembl_key_value)))(input)?;
+
+    let mut entry = Ensembl {
+        protein: StableId {
+            id: to_string(id),
+            version: version.map(to_string),
+        },
+        status: to_string(status),
+        location: GenomicLocation {
+            coord_system: to_string(coord_system),
+            assembly: to_string(assembly),
+            seq_region: to_string(seq_region),
+            start,
+            end,
+            strand,
+        },
+        ..Ensembl::default()
+    };
+
+    let (mut gene, mut transcript) = (None, None);
+    for (key, value) in key_values {
+        match key {
+            b"gene" => gene = Some(stable_id(value)?),
+            b"transcript" => transcript = Some(stable_id(value)?),
+            b"gene_biotype" => entry.gene_biotype = Some(to_string(value)),
+            b"transcript_biotype" => entry.transcript_biotype = Some(to_string(value)),
+            b"gene_symbol" => entry.gene_symbol = Some(to_string(value)),
+            b"description" => {
+                let (description, source) = ensembl_source(value);
+                let description = to_string(description).trim().to_string();
+                if !description.is_empty() {
+                    entry.description = Some(description);
+                }
+                entry.description_source = source.map(|(name, accession)| DescriptionSource {
+                    name: to_string(name),
+                    accession: to_string(accession),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    match (gene, transcript) {
+        (Some(gene), Some(transcript)) => {
+            entry.gene = gene;
+            entry.transcript = transcript;
+            Ok((input, entry))
+        }
+        _ => Err(nom::Err::Error((input, ErrorKind::Verify))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const TP53: &str = ">ENSP00000269305.4 pep chromosome:GRCh38:17:7661779:7687538:-1 gene:ENSG00000141510.18 transcript:ENST00000269305.9 gene_biotype:protein_coding transcript_biotype:protein_coding gene_symbol:TP53 description:tumor protein p53 [Source:HGNC Symbol;Acc:HGNC:11998]";
+
+    #[test]
+    fn test_ensembl_tp53() {
+        let entry = Ensembl {
+            protein: StableId {
+                id: "ENSP00000269305".to_string(),
+                version: Some("4".to_string()),
+            },
+            status: "pep".to_string(),
+            location: GenomicLocation {
+                coord_system: "chromosome".to_string(),
+                assembly: "GRCh38".to_string(),
+                seq_region: "17".to_string(),
+                start: 7661779,
+                end: 7687538,
+                strand: -1,
+            },
+            gene: StableId {
+                id: "ENSG00000141510".to_string(),
+                version: Some("18".to_string()),
+            },
+            transcript: StableId {
+                id: "ENST00000269305".to_string(),
+                version: Some("9".to_string()),
+            },
+            gene_biotype: Some("protein_coding".to_string()),
+            transcript_biotype: Some("protein_coding".to_string()),
+            gene_symbol: Some("TP53".to_string()),
+            description: Some("tumor protein p53".to_string()),
+            description_source: Some(DescriptionSource {
+                name: "HGNC Symbol".to_string(),
+                accession: "HGNC:11998".to_string(),
+            }),
+        };
+        let parsed = ensembl(TP53.as_bytes()).unwrap();
+        assert_eq!(parsed, entry);
+        assert_eq!(parsed.to_string(), TP53);
+    }
+
+    #[test]
+    fn test_ensembl_without_symbol_and_description() {
+        let header = ">ENSP00000452494.1 pep chromosome:GRCh38:14:22449113:22449125:1 gene:ENSG00000228985.1 transcript:ENST00000448914.1 gene_biotype:TR_D_gene transcript_biotype:TR_D_gene";
+        let parsed = ensembl(header.as_bytes()).unwrap();
+        assert_eq!(parsed.location.strand, 1);
+        assert_eq!(parsed.gene_symbol, None);
+        assert_eq!(parsed.description, None);
+        assert_eq!(parsed.to_string(), header);
+    }
+
+    #[test]
+    fn test_ensembl_legacy_status_and_scaffold() {
+        let header = ">FBpp0070000 pep:known scaffold:BDGP6:X:19961297:19969323:1 gene:FBgn0031081 transcript:FBtr0070000 description:Nep3";
+        let parsed = ensembl(header.as_bytes()).unwrap();
+        assert_eq!(parsed.status, "pep:known");
+        assert_eq!(parsed.protein.version, None);
+        assert_eq!(parsed.location.coord_system, "scaffold");
+        assert_eq!(parsed.description, Some("Nep3".to_string()));
+        assert_eq!(parsed.description_source, None);
+    }
+
+    #[test]
+    fn test_ensembl_missing_transcript() {
+        assert!(ensembl(
+            b">ENSP00000269305.4 pep chromosome:GRCh38:17:7661779:7687538:-1 gene:ENSG00000141510.18"
+        )
+        .is_err());
+    }
+}
@@ -10,6 +10,37 @@ pub enum UniProtHeaderError {
     ParsingError(String, String),
     /// Incomplete
     Incomplete,
+    /// Invalid filter expression at `{0}` : {1}
+    InvalidFilter(String, String),
+    /// Invalid template at `{0}` : {1}
+    InvalidTemplate(String, String),
+    /// No value for template placeholder `{{{0}}}`
+    MissingTemplateValue(String),
+    /// Header `{0}` is used more than once
+    DuplicateHeader(String),
+    /// Invalid short identifier length {0}, must be at least 2
+    InvalidShortIdLength(usize),
+    /// No free short identifier of {1} characters left for `{0}`
+    ShortIdExhausted(String, usize),
+    /// I/O error: {0}
+    Io(String),
+    /// XML error: {0}
+    Xml(String),
+    /// Line {0}: {1}
+    Line(usize, Box<UniProtHeaderError>),
+}
+
+impl UniProtHeaderError {
+    /// Attach a line number to the error
+    pub fn at_line(self, line: usize) -> Self {
+        UniProtHeaderError::Line(line, Box::new(self))
+    }
+}
+
+impl From<std::io::Error> for UniProtHeaderError {
+    fn from(err: std::io::Error) -> Self {
+        UniProtHeaderError::Io(err.to_string())
+    }
 }
 
 impl ParseError<&[u8]> for UniProtHeaderError {
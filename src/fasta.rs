@@ -0,0 +1,180 @@
+use crate::{error::UniProtHeaderError, filter::trim_newline};
+use std::io::{BufRead, Write};
+
+/// Sequence line width of UniProt FASTA files
+pub const DEFAULT_LINE_WIDTH: usize = 60;
+
+/// FASTA record
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Record {
+    /// Header line, with the leading `>` and without the newline
+    pub header: String,
+    /// Sequence, without line breaks
+    pub sequence: String,
+}
+
+impl Record {
+    /// Write the record, wrapping the sequence every `line_width` characters (no wrapping if 0)
+    pub fn write<W: Write>(
+        &self,
+        mut writer: W,
+        line_width: usize,
+    ) -> Result<(), UniProtHeaderError> {
+        writeln!(writer, "{}", self.header)?;
+        if self.sequence.is_empty() {
+            return Ok(());
+        }
+        if line_width == 0 {
+            writeln!(writer, "{}", self.sequence)?;
+        } else {
+            for line in self.sequence.as_bytes().chunks(line_width) {
+                writer.write_all(line)?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the records of a FASTA file
+///
+/// ```rust
+/// let fasta: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKSF\nLLVVT\n";
+///
+/// for record in uniprot_fasta_header::FastaReader::new(fasta) {
+///     let record = record.unwrap();
+///     assert_eq!(record.sequence, "MMKSFLLVVT");
+///     assert!(uniprot_fasta_header::uniprotkb(record.header.as_bytes()).is_ok());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FastaReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    next_header: Option<String>,
+    line_number: usize,
+}
+
+impl<R: BufRead> FastaReader<R> {
+    /// New FASTA reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::new(),
+            next_header: None,
+            line_number: 0,
+        }
+    }
+
+    fn read_line(&mut self) -> Result<bool, UniProtHeaderError> {
+        self.line.clear();
+        let read = self.reader.read_until(b'\n', &mut self.line)?;
+        self.line_number += 1;
+        Ok(read > 0)
+    }
+}
+
+impl<R: BufRead> Iterator for FastaReader<R> {
+    type Item = Result<Record, UniProtHeaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let header = match self.next_header.take() {
+            Some(header) => header,
+            None => loop {
+                match self.read_line() {
+                    Err(e) => return Some(Err(e)),
+                    Ok(false) => return None,
+                    Ok(true) => {
+                        let line = trim_newline(&self.line);
+                        if line.starts_with(b">") {
+                            break String::from_utf8_lossy(line).to_string();
+                        }
+                        if !line.iter().all(u8::is_ascii_whitespace) {
+                            return Some(Err(UniProtHeaderError::ParsingError(
+                                String::from_utf8_lossy(line).to_string(),
+                                "sequence before the first header".to_string(),
+                            )
+                            .at_line(self.line_number)));
+                        }
+                    }
+                }
+            },
+        };
+
+        let mut sequence = String::new();
+        loop {
+            match self.read_line() {
+                Err(e) => return Some(Err(e)),
+                Ok(false) => break,
+                Ok(true) => {
+                    let line = trim_newline(&self.line);
+                    if line.starts_with(b">") {
+                        self.next_header = Some(String::from_utf8_lossy(line).to_string());
+                        break;
+                    }
+                    sequence.extend(
+                        String::from_utf8_lossy(line)
+                            .chars()
+                            .filter(|c| !c.is_ascii_whitespace()),
+                    );
+                }
+            }
+        }
+
+        Some(Ok(Record { header, sequence }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn records() {
+        let fasta: &[u8] = b"\n>a desc\r\nMK\r\nQS\r\n>b\n>c\nAA \nA\n";
+        let records: Vec<Record> = FastaReader::new(fasta).map(Result::unwrap).collect();
+        assert_eq!(
+            records,
+            vec![
+                Record {
+                    header: ">a desc".to_string(),
+                    sequence: "MKQS".to_string()
+                },
+                Record {
+                    header: ">b".to_string(),
+                    sequence: String::new()
+                },
+                Record {
+                    header: ">c".to_string(),
+                    sequence: "AAA".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn non_ascii_sequence() {
+        let fasta: &[u8] = b">a\nMK\xC3\xA9\nQ\xFFS\n";
+        let record = FastaReader::new(fasta).next().unwrap().unwrap();
+        assert_eq!(record.sequence, "MKéQ\u{FFFD}S");
+    }
+
+    #[test]
+    fn sequence_without_header() {
+        let fasta: &[u8] = b"MKQS\n>a\n";
+        let err = FastaReader::new(fasta).next().unwrap().unwrap_err();
+        assert!(matches!(err, UniProtHeaderError::Line(1, _)));
+    }
+
+    #[test]
+    fn write() {
+        let record = Record {
+            header: ">a".to_string(),
+            sequence: "MKQSA".to_string(),
+        };
+        let mut out = Vec::new();
+        record.write(&mut out, 2).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), ">a\nMK\nQS\nA\n");
+    }
+}
@@ -0,0 +1,503 @@
+use crate::{error::UniProtHeaderError, uniprotkb, uniprotkb_iso, UniProtKB, UniProtKBIsoform};
+use nom::{
+    branch::alt,
+    bytes::complete::{is_not, tag, take_while1},
+    character::complete::{char, multispace0},
+    combinator::{all_consuming, map, opt, verify},
+    multi::{many0, separated_nonempty_list},
+    sequence::{delimited, pair, preceded, tuple},
+    IResult,
+};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    io::{BufRead, Write},
+    str::FromStr,
+};
+
+/// Header field usable in a filter expression
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Field {
+    /// `db`: database, `sp` or `tr`
+    Database,
+    /// `ac`, `accession`: UniProt accession number
+    Accession,
+    /// `id`, `entry_name`: UniProt entry name
+    EntryName,
+    /// `name`, `protein`: protein name
+    ProteinName,
+    /// `os`: organism name
+    OrganismName,
+    /// `ox`: NCBI taxonomic identifier
+    OrganismIdentifier,
+    /// `gn`, `gene`: gene name
+    GeneName,
+    /// `pe`: protein existence level, 1 to 5
+    ProteinExistence,
+    /// `sv`: sequence version
+    SequenceVersion,
+    /// `iso`, `isoform`: isoform number
+    Isoform,
+    /// `fragment`: the protein name ends with `(Fragment)`
+    Fragment,
+}
+
+impl FromStr for Field {
+    type Err = UniProtHeaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "db" => Ok(Field::Database),
+            "ac" | "accession" => Ok(Field::Accession),
+            "id" | "entry_name" => Ok(Field::EntryName),
+            "name" | "protein" => Ok(Field::ProteinName),
+            "os" => Ok(Field::OrganismName),
+            "ox" => Ok(Field::OrganismIdentifier),
+            "gn" | "gene" => Ok(Field::GeneName),
+            "pe" => Ok(Field::ProteinExistence),
+            "sv" => Ok(Field::SequenceVersion),
+            "iso" | "isoform" => Ok(Field::Isoform),
+            "fragment" => Ok(Field::Fragment),
+            _ => Err(UniProtHeaderError::InvalidFilter(
+                s.to_string(),
+                "unknown field".to_string(),
+            )),
+        }
+    }
+}
+
+/// Comparison operator
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Operator {
+    /// `=`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `<`
+    Lower,
+    /// `<=`
+    LowerOrEqual,
+    /// `>`
+    Greater,
+    /// `>=`
+    GreaterOrEqual,
+    /// `~`: case insensitive substring
+    Contains,
+}
+
+/// Filter expression tree
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expression {
+    /// All sub-expressions match
+    And(Vec<Expression>),
+    /// At least one sub-expression matches
+    Or(Vec<Expression>),
+    /// The sub-expression does not match
+    Not(Box<Expression>),
+    /// The field is present and not empty
+    Present(Field),
+    /// The field compares to the value
+    Compare(Field, Operator, String),
+    /// The field is equal to one of the values
+    In(Field, Vec<String>),
+}
+
+/// Access to the header fields used by filter expressions
+pub trait Filterable {
+    /// Value of a field, `None` if the header does not carry it
+    fn field(&self, field: Field) -> Option<Cow<'_, str>>;
+}
+
+fn fragment(protein_name: &str) -> Option<Cow<'_, str>> {
+    if protein_name.ends_with("(Fragment)") {
+        Some(Cow::Borrowed("true"))
+    } else {
+        None
+    }
+}
+
+impl Filterable for UniProtKB {
+    fn field(&self, field: Field) -> Option<Cow<'_, str>> {
+        match field {
+            Field::Database => Some(Cow::Borrowed(self.database.code())),
+            Field::Accession => Some(Cow::Borrowed(&self.identifier)),
+            Field::EntryName => Some(Cow::Borrowed(&self.entry_name)),
+            Field::ProteinName => Some(Cow::Borrowed(&self.protein_name)),
+            Field::OrganismName => Some(Cow::Borrowed(&self.organism_name)),
+            Field::OrganismIdentifier => self.organism_identifier.as_deref().map(Cow::Borrowed),
+            Field::GeneName => self.gene_name.as_deref().map(Cow::Borrowed),
+            Field::ProteinExistence => Some(Cow::Owned(self.protein_existence.level().to_string())),
+            Field::SequenceVersion => Some(Cow::Borrowed(&self.sequence_version)),
+            Field::Isoform => None,
+            Field::Fragment => fragment(&self.protein_name),
+        }
+    }
+}
+
+impl Filterable for UniProtKBIsoform {
+    fn field(&self, field: Field) -> Option<Cow<'_, str>> {
+        match field {
+            Field::Database => Some(Cow::Borrowed(self.database.code())),
+            Field::Accession => Some(Cow::Borrowed(&self.identifier)),
+            Field::EntryName => Some(Cow::Borrowed(&self.entry_name)),
+            Field::ProteinName => Some(Cow::Borrowed(&self.protein_name)),
+            Field::OrganismName => Some(Cow::Borrowed(&self.organism_name)),
+            Field::OrganismIdentifier => self.organism_identifier.as_deref().map(Cow::Borrowed),
+            Field::GeneName => self.gene_name.as_deref().map(Cow::Borrowed),
+            Field::ProteinExistence | Field::SequenceVersion => None,
+            Field::Isoform => Some(Cow::Borrowed(&self.isoform)),
+            Field::Fragment => fragment(&self.protein_name),
+        }
+    }
+}
+
+// Numbers are compared as numbers, everything else as text.
+// Database codes also accept the long names.
+fn compare(field: Field, value: &str, expected: &str) -> Ordering {
+    if let (Ok(a), Ok(b)) = (value.parse::<u64>(), expected.parse::<u64>()) {
+        return a.cmp(&b);
+    }
+
+    let expected = match (field, expected.to_ascii_lowercase().as_str()) {
+        (Field::Database, "swissprot") | (Field::Database, "swiss-prot") => "sp".to_string(),
+        (Field::Database, "trembl") => "tr".to_string(),
+        (Field::Database, e) => e.to_string(),
+        _ => expected.to_string(),
+    };
+
+    value.cmp(expected.as_str())
+}
+
+impl Expression {
+    fn matches<H: Filterable + ?Sized>(&self, header: &H) -> bool {
+        match self {
+            Expression::And(all) => all.iter().all(|e| e.matches(header)),
+            Expression::Or(any) => any.iter().any(|e| e.matches(header)),
+            Expression::Not(e) => !e.matches(header),
+            Expression::Present(field) => header.field(*field).is_some_and(|v| !v.is_empty()),
+            Expression::Compare(field, op, expected) => match header.field(*field) {
+                None => *op == Operator::NotEqual,
+                Some(value) => {
+                    if *op == Operator::Contains {
+                        return value
+                            .to_lowercase()
+                            .contains(expected.to_lowercase().as_str());
+                    }
+                    let ordering = compare(*field, &value, expected);
+                    match op {
+                        Operator::Equal => ordering == Ordering::Equal,
+                        Operator::NotEqual => ordering != Ordering::Equal,
+                        Operator::Lower => ordering == Ordering::Less,
+                        Operator::LowerOrEqual => ordering != Ordering::Greater,
+                        Operator::Greater => ordering == Ordering::Greater,
+                        Operator::GreaterOrEqual => ordering != Ordering::Less,
+                        Operator::Contains => unreachable!(),
+                    }
+                }
+            },
+            Expression::In(field, values) => match header.field(*field) {
+                None => false,
+                Some(value) => values
+                    .iter()
+                    .any(|v| compare(*field, &value, v) == Ordering::Equal),
+            },
+        }
+    }
+}
+
+/// Header filter
+///
+/// Filters are written as a small query language:
+///
+/// - comparisons: `pe<=2`, `db=sp`, `os="Homo sapiens"`, `name~kinase`
+/// - sets: `ox in {9606,10090}`
+/// - presence: `gn`, `fragment`
+/// - boolean logic: `and`, `or`, `not` and parentheses
+///
+/// ```rust
+/// use uniprot_fasta_header::Filter;
+///
+/// let filter: Filter = "db=sp and pe<=2 and ox in {9606,10090} and gn and not fragment"
+///     .parse()
+///     .unwrap();
+///
+/// let header = uniprot_fasta_header::uniprotkb(b">sp|Q9H553|ALG2_HUMAN Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1").unwrap();
+///
+/// assert!(filter.matches(&header));
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Filter {
+    /// Parsed expression
+    pub expression: Expression,
+}
+
+impl Filter {
+    /// Evaluate the filter against a parsed header
+    pub fn matches<H: Filterable + ?Sized>(&self, header: &H) -> bool {
+        self.expression.matches(header)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = UniProtHeaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match all_consuming(delimited(multispace0, or_expression, multispace0))(s) {
+            Ok((_, expression)) => Ok(Filter { expression }),
+            Err(nom::Err::Error((rest, kind))) | Err(nom::Err::Failure((rest, kind))) => Err(
+                UniProtHeaderError::InvalidFilter(rest.to_string(), kind.description().to_string()),
+            ),
+            Err(nom::Err::Incomplete(_)) => Err(UniProtHeaderError::Incomplete),
+        }
+    }
+}
+
+/// Copy the FASTA records whose header matches the filter
+///
+/// Records are copied byte for byte. Headers that are neither UniProtKB nor UniProtKB isoform headers never match.
+/// Returns the number of records written.
+pub fn filter_fasta<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    filter: &Filter,
+) -> Result<usize, UniProtHeaderError> {
+    let mut line = Vec::new();
+    let mut keep = false;
+    let mut counter: usize = 0;
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+
+        if line.starts_with(b">") {
+            let header = trim_newline(&line);
+            keep = match uniprotkb(header) {
+                Ok(parsed) => filter.matches(&parsed),
+                Err(_) => match uniprotkb_iso(header) {
+                    Ok(parsed) => filter.matches(&parsed),
+                    Err(_) => false,
+                },
+            };
+            if keep {
+                counter += 1;
+            }
+        }
+
+        if keep {
+            writer.write_all(&line)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(counter)
+}
+
+pub(crate) fn trim_newline(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/* --------------------------------- grammar -------------------------------- */
+
+fn is_word(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.'
+}
+
+fn word(input: &str) -> IResult<&str, &str> {
+    preceded(multispace0, take_while1(is_word))(input)
+}
+
+fn keyword<'a>(expected: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    verify(word, move |w: &str| w.eq_ignore_ascii_case(expected))
+}
+
+fn field(input: &str) -> IResult<&str, Field> {
+    let (rest, name) = word(input)?;
+    match name.parse() {
+        Ok(field) => Ok((rest, field)),
+        Err(_) => Err(nom::Err::Error((input, nom::error::ErrorKind::Tag))),
+    }
+}
+
+fn operator(input: &str) -> IResult<&str, Operator> {
+    preceded(
+        multispace0,
+        alt((
+            map(tag("<="), |_| Operator::LowerOrEqual),
+            map(tag(">="), |_| Operator::GreaterOrEqual),
+            map(tag("!="), |_| Operator::NotEqual),
+            map(tag("="), |_| Operator::Equal),
+            map(tag("<"), |_| Operator::Lower),
+            map(tag(">"), |_| Operator::Greater),
+            map(tag("~"), |_| Operator::Contains),
+        )),
+    )(input)
+}
+
+// Bare word or double quoted string
+fn value(input: &str) -> IResult<&str, String> {
+    preceded(
+        multispace0,
+        alt((
+            map(
+                delimited(tag("\""), opt(is_not("\"")), tag("\"")),
+                |v: Option<&str>| v.unwrap_or_default().to_string(),
+            ),
+            map(take_while1(is_word), |v: &str| v.to_string()),
+        )),
+    )(input)
+}
+
+fn set(input: &str) -> IResult<&str, Vec<String>> {
+    delimited(
+        preceded(multispace0, char('{')),
+        separated_nonempty_list(preceded(multispace0, char(',')), value),
+        preceded(multispace0, char('}')),
+    )(input)
+}
+
+fn predicate(input: &str) -> IResult<&str, Expression> {
+    alt((
+        map(tuple((field, operator, value)), |(f, o, v)| {
+            Expression::Compare(f, o, v)
+        }),
+        map(pair(field, preceded(keyword("in"), set)), |(f, s)| {
+            Expression::In(f, s)
+        }),
+        map(field, Expression::Present),
+    ))(input)
+}
+
+fn unary(input: &str) -> IResult<&str, Expression> {
+    alt((
+        map(preceded(keyword("not"), unary), |e| {
+            Expression::Not(Box::new(e))
+        }),
+        delimited(
+            preceded(multispace0, char('(')),
+            or_expression,
+            preceded(multispace0, char(')')),
+        ),
+        predicate,
+    ))(input)
+}
+
+fn and_expression(input: &str) -> IResult<&str, Expression> {
+    let (input, first) = unary(input)?;
+    let (input, mut rest) = many0(preceded(keyword("and"), unary))(input)?;
+    if rest.is_empty() {
+        Ok((input, first))
+    } else {
+        rest.insert(0, first);
+        Ok((input, Expression::And(rest)))
+    }
+}
+
+fn or_expression(input: &str) -> IResult<&str, Expression> {
+    let (input, first) = and_expression(input)?;
+    let (input, mut rest) = many0(preceded(keyword("or"), and_expression))(input)?;
+    if rest.is_empty() {
+        Ok((input, first))
+    } else {
+        rest.insert(0, first);
+        Ok((input, Expression::Or(rest)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    fn human() -> UniProtKB {
+        uniprotkb(b">sp|Q9H553|ALG2_HUMAN Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1").unwrap()
+    }
+
+    fn fragment() -> UniProtKB {
+        uniprotkb(b">tr|Q3SA23|Q3SA23_9HIV1 Protein Nef (Fragment) OS=Human immunodeficiency virus 1  OX=11676 GN=nef PE=3 SV=1").unwrap()
+    }
+
+    #[test]
+    fn parse_expression() {
+        let filter: Filter = "db=sp and (pe<=2 or not gn)".parse().unwrap();
+        assert_eq!(
+            filter.expression,
+            Expression::And(vec![
+                Expression::Compare(Field::Database, Operator::Equal, "sp".to_string()),
+                Expression::Or(vec![
+                    Expression::Compare(
+                        Field::ProteinExistence,
+                        Operator::LowerOrEqual,
+                        "2".to_string()
+                    ),
+                    Expression::Not(Box::new(Expression::Present(Field::GeneName))),
+                ]),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_set() {
+        let filter: Filter = "ox in {9606, 10090}".parse().unwrap();
+        assert_eq!(
+            filter.expression,
+            Expression::In(
+                Field::OrganismIdentifier,
+                vec!["9606".to_string(), "10090".to_string()]
+            )
+        );
+    }
+
+    #[test_case("db=sp and pe<=2 and ox=9606", true ; "sp pe ox")]
+    #[test_case("db=swissprot", true ; "long database name")]
+    #[test_case("ox in {9606,10090} and gn and not fragment", true ; "set presence")]
+    #[test_case("os=\"Homo sapiens\"", true ; "quoted")]
+    #[test_case("name~MANNOSYL", true ; "contains")]
+    #[test_case("pe>1", false ; "greater")]
+    #[test_case("db=tr or ox=10090", false ; "or")]
+    #[test_case("iso", false ; "no isoform")]
+    fn evaluate(expression: &str, expected: bool) {
+        let filter: Filter = expression.parse().unwrap();
+        pretty_assertions::assert_eq!(filter.matches(&human()), expected);
+    }
+
+    #[test]
+    fn evaluate_fragment() {
+        let filter: Filter = "fragment and db=tr".parse().unwrap();
+        assert!(filter.matches(&fragment()));
+        assert!(!filter.matches(&human()));
+    }
+
+    #[test]
+    fn evaluate_isoform() {
+        let header = uniprotkb_iso(b">sp|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2").unwrap();
+        let filter: Filter = "iso=2 and ox=9606".parse().unwrap();
+        assert!(filter.matches(&header));
+        let filter: Filter = "pe<=2".parse().unwrap();
+        assert!(!filter.matches(&header));
+    }
+
+    #[test_case("pe<=" ; "missing value")]
+    #[test_case("foo=1" ; "unknown field")]
+    #[test_case("db=sp and" ; "dangling and")]
+    #[test_case("(db=sp" ; "unbalanced")]
+    fn invalid(expression: &str) {
+        assert!(expression.parse::<Filter>().is_err());
+    }
+
+    #[test]
+    fn fasta() {
+        let input = b">sp|Q9H553|ALG2_HUMAN Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1\nMAEE\nKQ\n>tr|Q3SA23|Q3SA23_9HIV1 Protein Nef (Fragment) OS=Human immunodeficiency virus 1  OX=11676 GN=nef PE=3 SV=1\nMGGK\n";
+        let filter: Filter = "ox=9606".parse().unwrap();
+        let mut output = Vec::new();
+        let n = filter_fasta(&input[..], &mut output, &filter).unwrap();
+        assert_eq!(n, 1);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            ">sp|Q9H553|ALG2_HUMAN Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1\nMAEE\nKQ\n"
+        );
+    }
+}
@@ -0,0 +1,658 @@
+use crate::{
+    checksum::{check_crc64, ChecksumCheck},
+    error::UniProtHeaderError,
+    fasta::Record,
+    filter::{trim_newline, Field, Filterable},
+    idmapping::{CrossReference, CrossReferenceDb},
+    parser::scientific_name,
+    Database, ProteinExistence, UniProtKB,
+};
+use std::{borrow::Cow, io::BufRead};
+
+/// Gene of a `GN` line
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Gene {
+    /// `Name=`
+    pub name: Option<String>,
+    /// `Synonyms=`
+    pub synonyms: Vec<String>,
+    /// `OrderedLocusNames=`
+    pub ordered_locus_names: Vec<String>,
+    /// `ORFNames=`
+    pub orf_names: Vec<String>,
+}
+
+/// UniProtKB entry of a Swiss-Prot or TrEMBL flat file (`.dat`)
+///
+/// Evidence tags (`{ECO:...}`) are removed from the values.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UniProtEntry {
+    /// `ID`: reviewed (Swiss-Prot) or unreviewed (TrEMBL)
+    pub database: Database,
+    /// `ID`: entry name
+    pub entry_name: String,
+    /// `AC`: primary accession followed by the secondary accessions
+    pub accessions: Vec<String>,
+    /// `DT`: sequence version
+    pub sequence_version: String,
+    /// `DE`: recommended (`RecName`) or submitted (`SubName`) full name
+    pub protein_name: String,
+    /// `DE`: alternative full names (`AltName`)
+    pub alternative_names: Vec<String>,
+    /// `DE`: flags (`Precursor`, `Fragment`, `Fragments`)
+    pub flags: Vec<String>,
+    /// `GN`: genes
+    pub genes: Vec<Gene>,
+    /// `OS`: organism, with its common name and synonym
+    pub organism: String,
+    /// `OX`: NCBI taxonomic identifier
+    pub organism_identifier: Option<String>,
+    /// `OC`: taxonomic lineage
+    pub lineage: Vec<String>,
+    /// `PE`: protein existence
+    pub protein_existence: ProteinExistence,
+    /// `KW`: keywords
+    pub keywords: Vec<String>,
+    /// `DR`: cross-references, with the first identifier of each line, or the transcript, protein and gene of `Ensembl` lines
+    pub cross_references: Vec<CrossReference>,
+    /// `SQ`: sequence
+    pub sequence: String,
+    /// `SQ`: CRC64 of the sequence
+    pub crc64: Option<String>,
+}
+
+// Fields of a FASTA header that can be checked against the entry
+const HEADER_FIELDS: [Field; 9] = [
+    Field::Database,
+    Field::Accession,
+    Field::EntryName,
+    Field::ProteinName,
+    Field::OrganismName,
+    Field::OrganismIdentifier,
+    Field::GeneName,
+    Field::ProteinExistence,
+    Field::SequenceVersion,
+];
+
+impl UniProtEntry {
+    /// Primary accession
+    pub fn accession(&self) -> &str {
+        &self.accessions[0]
+    }
+
+    /// Scientific name of the organism, the trailing common name and synonym of `OS` are removed
+    ///
+    /// Parenthesized groups starting with an uppercase letter are considered common names,
+    /// strains and isolates (`Escherichia coli (strain K12)`) are kept.
+    pub fn organism_name(&self) -> &str {
+        scientific_name(&self.organism)
+    }
+
+    /// Gene name of the FASTA header: first gene name, ordered locus name or ORF name
+    pub fn gene_name(&self) -> Option<&str> {
+        let gene = self.genes.first()?;
+        gene.name
+            .as_deref()
+            .or_else(|| gene.ordered_locus_names.first().map(String::as_str))
+            .or_else(|| gene.orf_names.first().map(String::as_str))
+    }
+
+    /// Protein name of the FASTA header, with `(Fragment)` or `(Fragments)` for fragments
+    pub fn fasta_protein_name(&self) -> Cow<'_, str> {
+        match self
+            .flags
+            .iter()
+            .find(|flag| *flag == "Fragment" || *flag == "Fragments")
+        {
+            Some(flag) => Cow::Owned(format!("{} ({})", self.protein_name, flag)),
+            None => Cow::Borrowed(&self.protein_name),
+        }
+    }
+
+    /// UniProtKB FASTA header of the entry
+    pub fn fasta_header(&self) -> UniProtKB {
+        UniProtKB {
+            database: self.database.clone(),
+            identifier: self.accession().to_string(),
+            entry_name: self.entry_name.clone(),
+            protein_name: self.fasta_protein_name().to_string(),
+            organism_name: self.organism_name().to_string(),
+            organism_identifier: self.organism_identifier.clone(),
+            gene_name: self.gene_name().map(String::from),
+            protein_existence: self.protein_existence.clone(),
+            sequence_version: self.sequence_version.clone(),
+        }
+    }
+
+    /// FASTA record of the entry
+    pub fn to_record(&self) -> Record {
+        Record {
+            header: self.fasta_header().to_string(),
+            sequence: self.sequence.clone(),
+        }
+    }
+
+    /// Compare a sequence, from a FASTA file for instance, with the CRC64 of the entry
+    pub fn check_sequence(&self, sequence: &str) -> ChecksumCheck {
+        match &self.crc64 {
+            Some(crc64) => check_crc64(sequence.as_bytes(), crc64),
+            None => ChecksumCheck::Unknown,
+        }
+    }
+
+    /// Fields of a FASTA header that differ from the entry
+    pub fn check_header(&self, header: &UniProtKB) -> Vec<Field> {
+        let expected = self.fasta_header();
+        HEADER_FIELDS
+            .iter()
+            .filter(|&&field| expected.field(field) != header.field(field))
+            .copied()
+            .collect()
+    }
+}
+
+// Remove the evidence tags and the trailing separator of a value
+fn clean(value: &str) -> String {
+    let mut cleaned = String::with_capacity(value.len());
+    let mut depth = 0;
+    for c in value.chars() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            _ if depth == 0 => cleaned.push(c),
+            _ => (),
+        }
+    }
+    cleaned
+        .trim()
+        .trim_end_matches([';', '.', ','])
+        .trim()
+        .to_string()
+}
+
+// Items of a `;` separated list spanning several lines
+fn items(text: &str) -> Vec<String> {
+    text.split(';')
+        .map(clean)
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+// Identifier of a `DR` item, without the isoform of the last item (`ENSBTAG00000002536. [P02668-1]`)
+fn dr_identifier(item: &str) -> Option<String> {
+    item.split_whitespace()
+        .next()
+        .map(|identifier| identifier.trim_end_matches('.'))
+        .filter(|identifier| !identifier.is_empty() && *identifier != "-")
+        .map(String::from)
+}
+
+fn parse_genes(lines: &[&str]) -> Vec<Gene> {
+    let mut genes = Vec::new();
+    for block in lines.split(|line| line.trim() == "and") {
+        let text = block.join(" ");
+        let mut gene = Gene::default();
+        for item in text.split(';') {
+            let (key, value) = match item.split_once('=') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            // Evidence tags can hold commas, they go before the values are split
+            let value = clean(value);
+            let mut values = value.split(',').map(clean).filter(|v| !v.is_empty());
+            match key.trim() {
+                "Name" => gene.name = values.next(),
+                "Synonyms" => gene.synonyms.extend(values),
+                "OrderedLocusNames" => gene.ordered_locus_names.extend(values),
+                "ORFNames" => gene.orf_names.extend(values),
+                _ => (),
+            }
+        }
+        if gene != Gene::default() {
+            genes.push(gene);
+        }
+    }
+    genes
+}
+
+// Line code and content, `     ` for sequence lines
+fn split_line(line: &str) -> (&str, &str) {
+    match line.get(..2) {
+        Some(code) => (code, line.get(5..).unwrap_or("")),
+        None => (line, ""),
+    }
+}
+
+fn parse_entry(lines: &[String], line_number: usize) -> Result<UniProtEntry, UniProtHeaderError> {
+    let error = |message: &str| {
+        UniProtHeaderError::ParsingError(
+            lines.first().cloned().unwrap_or_default(),
+            message.to_string(),
+        )
+        .at_line(line_number)
+    };
+
+    let mut id = None;
+    let mut accessions = Vec::new();
+    let mut sequence_version = None;
+    let mut protein_name: Option<String> = None;
+    let mut alternative_names = Vec::new();
+    let mut flags = Vec::new();
+    let mut gene_lines = Vec::new();
+    let mut organism = Vec::new();
+    let mut organism_identifier = None;
+    let mut lineage = String::new();
+    let mut protein_existence = None;
+    let mut keywords = String::new();
+    let mut cross_references = Vec::new();
+    let mut sequence = String::new();
+    let mut crc64 = None;
+    let mut subsection = false;
+
+    for line in lines {
+        let (code, content) = split_line(line);
+        match code {
+            "ID" => id = Some(content),
+            "AC" => accessions.extend(items(content)),
+            "DT" => {
+                if let Some(version) = content
+                    .split_once("sequence version ")
+                    .map(|(_, version)| version.trim_end_matches('.').trim())
+                {
+                    sequence_version = Some(version.to_string());
+                }
+            }
+            "DE" => {
+                let indent = content.len() - content.trim_start().len();
+                let content = content.trim_start();
+                if indent == 0 {
+                    subsection = content == "Contains:" || content == "Includes:";
+                }
+                if subsection || indent > 0 {
+                    continue;
+                }
+                if let Some((kind, name)) = content.split_once(": Full=") {
+                    let name = clean(name);
+                    match kind {
+                        "RecName" | "SubName" if protein_name.is_none() => {
+                            protein_name = Some(name)
+                        }
+                        "AltName" => alternative_names.push(name),
+                        _ => (),
+                    }
+                } else if let Some(values) = content.strip_prefix("Flags:") {
+                    flags.extend(items(values));
+                }
+            }
+            "GN" => gene_lines.push(content),
+            "OS" => organism.push(content.trim()),
+            "OC" => {
+                lineage.push(' ');
+                lineage.push_str(content);
+            }
+            "OX" => {
+                organism_identifier = content
+                    .split_once("NCBI_TaxID=")
+                    .map(|(_, id)| id.chars().take_while(char::is_ascii_digit).collect());
+            }
+            "PE" => {
+                protein_existence = content
+                    .get(..1)
+                    .and_then(|level| level.parse().ok())
+                    .and_then(ProteinExistence::from_level);
+            }
+            "KW" => {
+                keywords.push(' ');
+                keywords.push_str(content);
+            }
+            "DR" => {
+                let fields = items(content);
+                match fields.as_slice() {
+                    // Transcript, protein and gene identifiers
+                    [database, transcript, protein, gene, ..] if database == "Ensembl" => {
+                        for (database, identifier) in [
+                            (CrossReferenceDb::EnsemblTrs, transcript),
+                            (CrossReferenceDb::EnsemblPro, protein),
+                            (CrossReferenceDb::Ensembl, gene),
+                        ] {
+                            if let Some(identifier) = dr_identifier(identifier) {
+                                cross_references.push(CrossReference {
+                                    database,
+                                    identifier,
+                                });
+                            }
+                        }
+                    }
+                    [database, identifier, ..] => {
+                        if let Some(identifier) = dr_identifier(identifier) {
+                            cross_references.push(CrossReference {
+                                database: database.parse()?,
+                                identifier,
+                            });
+                        }
+                    }
+                    _ => (),
+                }
+            }
+            "SQ" => {
+                crc64 = content
+                    .split(';')
+                    .find_map(|field| field.trim().strip_suffix(" CRC64"))
+                    .map(|crc| crc.trim().to_string());
+            }
+            "  " => sequence.extend(line.chars().filter(|c| !c.is_whitespace())),
+            _ => (),
+        }
+    }
+
+    let id: Vec<&str> = id
+        .ok_or_else(|| error("missing ID line"))?
+        .split_whitespace()
+        .collect();
+    let (entry_name, database) = match id.as_slice() {
+        [entry_name, "Reviewed;", ..] => (entry_name, Database::SwissProt),
+        [entry_name, "Unreviewed;", ..] => (entry_name, Database::TrEMBL),
+        _ => return Err(error("invalid ID line")),
+    };
+    if accessions.is_empty() {
+        return Err(error("missing AC line"));
+    }
+
+    Ok(UniProtEntry {
+        database,
+        entry_name: entry_name.to_string(),
+        accessions,
+        sequence_version: sequence_version.ok_or_else(|| error("missing sequence version"))?,
+        protein_name: protein_name.ok_or_else(|| error("missing protein name"))?,
+        alternative_names,
+        flags,
+        genes: parse_genes(&gene_lines),
+        organism: organism.join(" ").trim_end_matches('.').to_string(),
+        organism_identifier,
+        lineage: items(&lineage),
+        protein_existence: protein_existence.ok_or_else(|| error("missing PE line"))?,
+        keywords: items(&keywords),
+        cross_references,
+        sequence,
+        crc64,
+    })
+}
+
+/// Iterator over the entries of a Swiss-Prot or TrEMBL flat file (`uniprot_sprot.dat`)
+///
+/// ```rust
+/// let dat: &[u8] = b"ID   CASK_BOVIN              Reviewed;         10 AA.
+/// AC   P02668; P29358;
+/// DT   21-JUL-1986, sequence version 1.
+/// DE   RecName: Full=Kappa-casein;
+/// GN   Name=CSN3; Synonyms=CSN10, CSNK;
+/// OS   Bos taurus (Bovine).
+/// OX   NCBI_TaxID=9913;
+/// PE   1: Evidence at protein level;
+/// SQ   SEQUENCE   10 AA;  1160 MW;  0000000000000000 CRC64;
+///      MMKSFFLVVT
+/// //
+/// ";
+///
+/// for entry in uniprot_fasta_header::EntryReader::new(dat) {
+///     let entry = entry.unwrap();
+///     assert_eq!(
+///         entry.fasta_header().to_string(),
+///         ">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1"
+///     );
+/// }
+/// ```
+#[derive(Debug)]
+pub struct EntryReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    line_number: usize,
+}
+
+impl<R: BufRead> EntryReader<R> {
+    /// New flat file reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::new(),
+            line_number: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for EntryReader<R> {
+    type Item = Result<UniProtEntry, UniProtHeaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut lines = Vec::new();
+        let mut first_line = 0;
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Err(e) => return Some(Err(e.into())),
+                Ok(0) if lines.is_empty() => return None,
+                Ok(0) => {
+                    return Some(Err(UniProtHeaderError::ParsingError(
+                        lines.remove(0),
+                        "entry not terminated by //".to_string(),
+                    )
+                    .at_line(first_line)))
+                }
+                Ok(_) => self.line_number += 1,
+            }
+            let line = String::from_utf8_lossy(trim_newline(&self.line)).to_string();
+            if line.starts_with("//") {
+                return Some(parse_entry(&lines, first_line));
+            }
+            if line.trim().is_empty() {
+                continue;
+            }
+            if lines.is_empty() {
+                first_line = self.line_number;
+            }
+            lines.push(line);
+        }
+    }
+}
+
+/// Parse a single flat file entry
+pub fn uniprot_entry(text: &[u8]) -> Result<UniProtEntry, UniProtHeaderError> {
+    EntryReader::new(text)
+        .next()
+        .unwrap_or(Err(UniProtHeaderError::Incomplete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{idmapping::CrossReferenceDb, uniprotkb};
+    use pretty_assertions::assert_eq;
+
+    const CASK_BOVIN: &str = "\
+ID   CASK_BOVIN              Reviewed;         190 AA.
+AC   P02668; P29358; Q28399;
+DT   21-JUL-1986, integrated into UniProtKB/Swiss-Prot.
+DT   21-JUL-1986, sequence version 1.
+DT   12-AUG-2020, entry version 167.
+DE   RecName: Full=Kappa-casein;
+DE   Contains:
+DE     RecName: Full=Casoxin-C;
+DE   Contains:
+DE     RecName: Full=Casoxin 6;
+DE   Flags: Precursor;
+GN   Name=CSN3; Synonyms=CSN10, CSNK;
+OS   Bos taurus (Bovine).
+OC   Eukaryota; Metazoa; Chordata; Craniata; Vertebrata; Euteleostomi;
+OC   Mammalia; Eutheria; Laurasiatheria; Artiodactyla; Ruminantia; Pecora;
+OC   Bovidae; Bovinae; Bos.
+OX   NCBI_TaxID=9913;
+RN   [1]
+RP   NUCLEOTIDE SEQUENCE [MRNA].
+DR   EMBL; X14908; CAA33066.1; -; mRNA.
+DR   RefSeq; NP_776719.1; NM_174294.2.
+DR   PDB; 5LZ5; X-ray; 2.10 A; C=21-36.
+DR   Ensembl; ENSBTAT00000003311; ENSBTAP00000003311; ENSBTAG00000002536. [P02668-1]
+PE   1: Evidence at protein level;
+KW   3D-structure; Direct protein sequencing; Glycoprotein; Mammary gland;
+KW   Milk protein; Phosphoprotein; Reference proteome; Secreted; Signal.
+FT   SIGNAL          1..21
+SQ   SEQUENCE   190 AA;  21269 MW;  A4A6D6CE4BB5BEB4 CRC64;
+     MMKSFFLVVT ILALTLPFLG AQEQNQEQPI RCEKDERFFS DKIAKYIPIQ YVLSRYPSYG
+     LNYYQQKPVA
+//
+";
+
+    const TREMBL: &str = "\
+ID   A0A023GPI8_CANAL        Unreviewed;       237 AA.
+AC   A0A023GPI8;
+DT   09-JUL-2014, sequence version 1.
+DE   SubName: Full=Lectin alpha chain {ECO:0000313|EMBL:CDO35891.1};
+DE   Flags: Fragment;
+GN   OrderedLocusNames=CLP_0001 {ECO:0000313|EMBL:CDO35891.1}; ORFNames=ORF1;
+OS   Canavalia lineata (Beach bean) (Dolichos lineatus).
+OX   NCBI_TaxID=28957 {ECO:0000313|EMBL:CDO35891.1};
+PE   4: Predicted;
+SQ   SEQUENCE   10 AA;  1000 MW;  0000000000000000 CRC64;
+     ADTIVAVELD
+//
+";
+
+    #[test]
+    fn swissprot_entry() {
+        let entry = uniprot_entry(CASK_BOVIN.as_bytes()).unwrap();
+        assert_eq!(entry.accessions, vec!["P02668", "P29358", "Q28399"]);
+        assert_eq!(entry.protein_name, "Kappa-casein");
+        assert_eq!(entry.flags, vec!["Precursor"]);
+        assert_eq!(
+            entry.genes,
+            vec![Gene {
+                name: Some("CSN3".to_string()),
+                synonyms: vec!["CSN10".to_string(), "CSNK".to_string()],
+                ..Gene::default()
+            }]
+        );
+        assert_eq!(entry.lineage.len(), 15);
+        assert_eq!(entry.lineage[14], "Bos");
+        assert_eq!(entry.keywords[0], "3D-structure");
+        assert_eq!(entry.keywords.len(), 9);
+        assert_eq!(
+            entry.cross_references[1],
+            CrossReference {
+                database: CrossReferenceDb::RefSeq,
+                identifier: "NP_776719.1".to_string(),
+            }
+        );
+        assert_eq!(
+            entry.cross_references[3..]
+                .iter()
+                .map(|r| (r.database.name(), r.identifier.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Ensembl_TRS", "ENSBTAT00000003311"),
+                ("Ensembl_PRO", "ENSBTAP00000003311"),
+                ("Ensembl", "ENSBTAG00000002536"),
+            ]
+        );
+        assert_eq!(entry.sequence.len(), 70);
+        assert_eq!(entry.crc64.as_deref(), Some("A4A6D6CE4BB5BEB4"));
+        assert_eq!(
+            entry.check_sequence(&entry.sequence),
+            ChecksumCheck::Mismatch {
+                expected: "A4A6D6CE4BB5BEB4".to_string(),
+                computed: "B26F7F1E0156904D".to_string(),
+            }
+        );
+        assert_eq!(
+            entry.fasta_header().to_string(),
+            ">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1"
+        );
+    }
+
+    #[test]
+    fn trembl_entry() {
+        let entry = uniprot_entry(TREMBL.as_bytes()).unwrap();
+        assert_eq!(
+            entry.organism,
+            "Canavalia lineata (Beach bean) (Dolichos lineatus)"
+        );
+        assert_eq!(
+            entry.to_record(),
+            Record {
+                header: ">tr|A0A023GPI8|A0A023GPI8_CANAL Lectin alpha chain (Fragment) OS=Canavalia lineata OX=28957 GN=CLP_0001 PE=4 SV=1".to_string(),
+                sequence: "ADTIVAVELD".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn several_entries() {
+        let dat = format!("{}{}", CASK_BOVIN, TREMBL);
+        let entries: Vec<UniProtEntry> = EntryReader::new(dat.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].accession(), "A0A023GPI8");
+    }
+
+    #[test]
+    fn gene_evidence_tags() {
+        let genes = parse_genes(&[
+            "Name=ABC {ECO:0000313|EMBL:CDO35891.1};",
+            "Synonyms=DEF {ECO:0000269|PubMed:1, ECO:0000269|PubMed:2}, GHI;",
+            "ORFNames=ORF1 {ECO:0000269|PubMed:1, ECO:0000269|PubMed:2};",
+        ]);
+        assert_eq!(
+            genes,
+            vec![Gene {
+                name: Some("ABC".to_string()),
+                synonyms: vec!["DEF".to_string(), "GHI".to_string()],
+                orf_names: vec!["ORF1".to_string()],
+                ..Gene::default()
+            }]
+        );
+    }
+
+    #[test]
+    fn organism_names() {
+        let mut entry = uniprot_entry(CASK_BOVIN.as_bytes()).unwrap();
+        entry.organism =
+            "Saccharomyces cerevisiae (strain ATCC 204508 / S288c) (Baker's yeast)".to_string();
+        assert_eq!(
+            entry.organism_name(),
+            "Saccharomyces cerevisiae (strain ATCC 204508 / S288c)"
+        );
+        entry.organism = "Escherichia coli (strain K12)".to_string();
+        assert_eq!(entry.organism_name(), "Escherichia coli (strain K12)");
+    }
+
+    #[test]
+    fn check_header() {
+        let entry = uniprot_entry(CASK_BOVIN.as_bytes()).unwrap();
+        let header = uniprotkb(
+            b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN10 PE=1 SV=2",
+        )
+        .unwrap();
+        assert_eq!(
+            entry.check_header(&header),
+            vec![Field::GeneName, Field::SequenceVersion]
+        );
+        assert!(entry.check_header(&entry.fasta_header()).is_empty());
+    }
+
+    #[test]
+    fn invalid_entries() {
+        let missing_pe = CASK_BOVIN.replace("PE   1: Evidence at protein level;\n", "");
+        assert!(matches!(
+            uniprot_entry(missing_pe.as_bytes()).unwrap_err(),
+            UniProtHeaderError::Line(1, _)
+        ));
+        let unterminated = CASK_BOVIN.replace("//\n", "");
+        assert!(uniprot_entry(unterminated.as_bytes()).is_err());
+        assert_eq!(
+            uniprot_entry(b"").unwrap_err(),
+            UniProtHeaderError::Incomplete
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use crate::{
+    ensembl::{ensembl, Ensembl},
+    error::UniProtHeaderError,
+    filter::{trim_newline, Field, Filterable},
+    header::FastaHeader,
+    ncbi::{ncbi, Ncbi},
+    parser::ncbi_id,
+    pdb_seqres::{pdb_seqres, PdbSeqres},
+    uniparc::{uniparc, UniParc},
+    uniprotkb::{uniprotkb, UniProtKB},
+    uniprotkb_isoform::{uniprotkb_iso, UniProtKBIsoform},
+    uniref::{uniref, UniRef},
+};
+use memchr::memmem;
+use std::{borrow::Cow, cmp::Reverse, fmt, io::BufRead};
+
+/// Header formats
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum HeaderFormat {
+    /// UniProtKB
+    UniProtKB,
+    /// UniProtKB isoform
+    UniProtKBIsoform,
+    /// UniRef cluster
+    UniRef,
+    /// UniParc
+    UniParc,
+    /// NCBI RefSeq or GenBank
+    Ncbi,
+    /// Ensembl peptide
+    Ensembl,
+    /// PDB SEQRES
+    PdbSeqres,
+    /// None of the above
+    Unknown,
+}
+
+impl HeaderFormat {
+    /// Formats in the order [`parse_any`] tries them
+    ///
+    /// NCBI only checks the shape of the accession, it comes last.
+    pub const ORDER: [HeaderFormat; 7] = [
+        HeaderFormat::UniProtKB,
+        HeaderFormat::UniProtKBIsoform,
+        HeaderFormat::UniRef,
+        HeaderFormat::UniParc,
+        HeaderFormat::PdbSeqres,
+        HeaderFormat::Ensembl,
+        HeaderFormat::Ncbi,
+    ];
+
+    /// Short name of the format
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeaderFormat::UniProtKB => "uniprotkb",
+            HeaderFormat::UniProtKBIsoform => "uniprotkb_isoform",
+            HeaderFormat::UniRef => "uniref",
+            HeaderFormat::UniParc => "uniparc",
+            HeaderFormat::Ncbi => "ncbi",
+            HeaderFormat::Ensembl => "ensembl",
+            HeaderFormat::PdbSeqres => "pdb_seqres",
+            HeaderFormat::Unknown => "unknown",
+        }
+    }
+
+    // Cheap check before running the parser
+    fn may_match(self, header: &[u8]) -> bool {
+        match self {
+            HeaderFormat::UniProtKB | HeaderFormat::UniProtKBIsoform => {
+                header.starts_with(b">sp|") || header.starts_with(b">tr|")
+            }
+            HeaderFormat::UniRef => header.starts_with(b">UniRef"),
+            HeaderFormat::UniParc => header.starts_with(b">UPI"),
+            HeaderFormat::PdbSeqres => {
+                header.get(5) == Some(&b'_') && memmem::find(header, b" mol:").is_some()
+            }
+            HeaderFormat::Ensembl => memmem::find(header, b" gene:").is_some(),
+            HeaderFormat::Ncbi => header
+                .strip_prefix(b">")
+                .is_some_and(|rest| ncbi_id(rest).is_ok()),
+            HeaderFormat::Unknown => false,
+        }
+    }
+
+    fn parse(self, header: &[u8]) -> Result<Header, UniProtHeaderError> {
+        match self {
+            HeaderFormat::UniProtKB => uniprotkb(header).map(Header::UniProtKB),
+            HeaderFormat::UniProtKBIsoform => uniprotkb_iso(header).map(Header::UniProtKBIsoform),
+            HeaderFormat::UniRef => uniref(header).map(Header::UniRef),
+            HeaderFormat::UniParc => uniparc(header).map(Header::UniParc),
+            HeaderFormat::Ncbi => ncbi(header).map(Header::Ncbi),
+            HeaderFormat::Ensembl => ensembl(header).map(Header::Ensembl),
+            HeaderFormat::PdbSeqres => pdb_seqres(header).map(Header::PdbSeqres),
+            HeaderFormat::Unknown => Err(UniProtHeaderError::ParsingError(
+                String::from_utf8_lossy(header).to_string(),
+                "unknown header format".to_string(),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for HeaderFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Header of any supported format
+#[derive(Debug, PartialEq, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum Header {
+    /// UniProtKB
+    UniProtKB(UniProtKB),
+    /// UniProtKB isoform
+    UniProtKBIsoform(UniProtKBIsoform),
+    /// UniRef cluster
+    UniRef(UniRef),
+    /// UniParc
+    UniParc(UniParc),
+    /// NCBI RefSeq or GenBank
+    Ncbi(Ncbi),
+    /// Ensembl peptide
+    Ensembl(Ensembl),
+    /// PDB SEQRES
+    PdbSeqres(PdbSeqres),
+}
+
+impl Header {
+    /// Format of the header
+    pub fn format(&self) -> HeaderFormat {
+        match self {
+            Header::UniProtKB(_) => HeaderFormat::UniProtKB,
+            Header::UniProtKBIsoform(_) => HeaderFormat::UniProtKBIsoform,
+            Header::UniRef(_) => HeaderFormat::UniRef,
+            Header::UniParc(_) => HeaderFormat::UniParc,
+            Header::Ncbi(_) => HeaderFormat::Ncbi,
+            Header::Ensembl(_) => HeaderFormat::Ensembl,
+            Header::PdbSeqres(_) => HeaderFormat::PdbSeqres,
+        }
+    }
+
+    fn inner(&self) -> &dyn FastaHeader {
+        match self {
+            Header::UniProtKB(h) => h,
+            Header::UniProtKBIsoform(h) => h,
+            Header::UniRef(h) => h,
+            Header::UniParc(h) => h,
+            Header::Ncbi(h) => h,
+            Header::Ensembl(h) => h,
+            Header::PdbSeqres(h) => h,
+        }
+    }
+}
+
+/// Write the fasta header in its own format
+impl fmt::Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Header::UniProtKB(h) => h.fmt(f),
+            Header::UniProtKBIsoform(h) => h.fmt(f),
+            Header::UniRef(h) => h.fmt(f),
+            Header::UniParc(h) => h.fmt(f),
+            Header::Ncbi(h) => h.fmt(f),
+            Header::Ensembl(h) => h.fmt(f),
+            Header::PdbSeqres(h) => h.fmt(f),
+        }
+    }
+}
+
+impl FastaHeader for Header {
+    fn identifier(&self) -> Cow<'_, str> {
+        self.inner().identifier()
+    }
+
+    fn organism_name(&self) -> Option<&str> {
+        self.inner().organism_name()
+    }
+
+    fn taxon_id(&self) -> Option<&str> {
+        self.inner().taxon_id()
+    }
+
+    fn gene_name(&self) -> Option<&str> {
+        self.inner().gene_name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner().description()
+    }
+
+    fn source_database(&self) -> &'static str {
+        self.inner().source_database()
+    }
+}
+
+impl Filterable for Header {
+    fn field(&self, field: Field) -> Option<Cow<'_, str>> {
+        match self {
+            Header::UniProtKB(h) => h.field(field),
+            Header::UniProtKBIsoform(h) => h.field(field),
+            Header::UniRef(h) => h.field(field),
+            Header::UniParc(h) => h.field(field),
+            Header::Ncbi(h) => h.field(field),
+            Header::Ensembl(h) => h.field(field),
+            Header::PdbSeqres(h) => h.field(field),
+        }
+    }
+}
+
+/// Parse a header of any supported format
+///
+/// Formats are tried in the order of [`HeaderFormat::ORDER`], skipping those ruled out by a quick look at the header.
+///
+/// ```rust
+/// use uniprot_fasta_header::{FastaHeader, HeaderFormat};
+///
+/// let parsed = uniprot_fasta_header::parse_any(b">UniRef90_Q9H553 Alpha-1,3/1,6-mannosyltransferase ALG2 n=2 Tax=Homo sapiens TaxID=9606 RepID=ALG2_HUMAN").unwrap();
+///
+/// assert_eq!(parsed.format(), HeaderFormat::UniRef);
+/// assert_eq!(parsed.identifier(), "UniRef90_Q9H553");
+/// ```
+pub fn parse_any(header: &[u8]) -> Result<Header, UniProtHeaderError> {
+    let mut error = None;
+    for format in HeaderFormat::ORDER.iter() {
+        if !format.may_match(header) {
+            continue;
+        }
+        match format.parse(header) {
+            Ok(parsed) => return Ok(parsed),
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        }
+    }
+    Err(error.unwrap_or_else(|| HeaderFormat::Unknown.parse(header).unwrap_err()))
+}
+
+/// Format of a header, [`HeaderFormat::Unknown`] if no parser accepts it
+pub fn detect_format(header: &[u8]) -> HeaderFormat {
+    parse_any(header).map_or(HeaderFormat::Unknown, |parsed| parsed.format())
+}
+
+/// Formats found in a sample of headers
+#[derive(Debug, PartialEq, Clone)]
+pub struct FormatReport {
+    /// Most frequent format
+    pub format: HeaderFormat,
+    /// Share of the sampled headers in the most frequent format, from 0 to 1
+    pub confidence: f64,
+    /// Number of sampled headers
+    pub sampled: usize,
+    /// Number of headers for each format found, most frequent first
+    pub counts: Vec<(HeaderFormat, usize)>,
+}
+
+/// Detect the format of a FASTA file from its first `sample` headers
+///
+/// ```rust
+/// let fasta: &[u8] = b">NP_000005.3 alpha-2-macroglobulin [Homo sapiens]\nMGKN\n>XP_011518520.1 protein [Homo sapiens]\nMPLL\n";
+///
+/// let report = uniprot_fasta_header::sniff_format(fasta, 100).unwrap();
+///
+/// assert_eq!(report.format, uniprot_fasta_header::HeaderFormat::Ncbi);
+/// assert_eq!(report.confidence, 1.0);
+/// ```
+pub fn sniff_format<R: BufRead>(
+    mut reader: R,
+    sample: usize,
+) -> Result<FormatReport, UniProtHeaderError> {
+    let mut counts: Vec<(HeaderFormat, usize)> = Vec::new();
+    let mut sampled = 0;
+    let mut line = Vec::new();
+
+    while sampled < sample {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let header = trim_newline(&line);
+        if !header.starts_with(b">") {
+            continue;
+        }
+
+        let format = detect_format(header);
+        match counts.iter_mut().find(|(f, _)| *f == format) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((format, 1)),
+        }
+        sampled += 1;
+    }
+
+    // stable sort: ties go to the format seen first
+    counts.sort_by_key(|&(_, count)| Reverse(count));
+    let (format, confidence) = match counts.first() {
+        Some(&(format, count)) => (format, count as f64 / sampled as f64),
+        None => (HeaderFormat::Unknown, 0.0),
+    };
+
+    Ok(FormatReport {
+        format,
+        confidence,
+        sampled,
+        counts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case(">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1", HeaderFormat::UniProtKB ; "uniprotkb")]
+    #[test_case(">sp|P12345|AATM_RABIT Aspartate aminotransferase OS=Oryctolagus cuniculus GN=GOT2 PE=1 SV=2", HeaderFormat::UniProtKB ; "uniprotkb legacy")]
+    #[test_case(">sp|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2", HeaderFormat::UniProtKBIsoform ; "isoform")]
+    #[test_case(">UniRef90_Q9H553 Alpha-1,3/1,6-mannosyltransferase ALG2 n=2 Tax=Homo sapiens TaxID=9606 RepID=ALG2_HUMAN", HeaderFormat::UniRef ; "uniref")]
+    #[test_case(">UPI0000000001 status=active", HeaderFormat::UniParc ; "uniparc")]
+    #[test_case(">NP_000005.3 alpha-2-macroglobulin isoform a precursor [Homo sapiens]", HeaderFormat::Ncbi ; "ncbi")]
+    #[test_case(">ENSP00000269305.4 pep chromosome:GRCh38:17:7661779:7687538:-1 gene:ENSG00000141510.18 transcript:ENST00000269305.9 gene_symbol:TP53", HeaderFormat::Ensembl ; "ensembl")]
+    #[test_case(">101m_A mol:protein length:154  MYOGLOBIN", HeaderFormat::PdbSeqres ; "pdb")]
+    #[test_case(">sp|broken", HeaderFormat::Unknown ; "broken uniprotkb")]
+    #[test_case("MKQS", HeaderFormat::Unknown ; "sequence")]
+    #[test_case(">foo", HeaderFormat::Unknown ; "custom identifier")]
+    #[test_case(">seq1 OS=Homo sapiens OX=9606", HeaderFormat::Unknown ; "key value")]
+    #[test_case(">gi|4557225|ref|NP_000005.2| alpha-2-macroglobulin precursor [Homo sapiens]", HeaderFormat::Ncbi ; "ncbi geninfo")]
+    fn formats(header: &str, expected: HeaderFormat) {
+        pretty_assertions::assert_eq!(detect_format(header.as_bytes()), expected);
+        if expected != HeaderFormat::Unknown {
+            let parsed = parse_any(header.as_bytes()).unwrap();
+            pretty_assertions::assert_eq!(parsed.format(), expected);
+        }
+    }
+
+    #[test]
+    fn parse_any_error() {
+        assert!(matches!(
+            parse_any(b"MKQS").unwrap_err(),
+            UniProtHeaderError::ParsingError(_, _)
+        ));
+    }
+
+    #[test]
+    fn sniff_mixed() {
+        let fasta: &[u8] = b">UPI0000000001 status=active\nMK\n>UPI0000000002 status=active\nMK\n>101m_A mol:protein length:2  MYOGLOBIN\nMK\n>UPI0000000003\nMK\n";
+        let report = sniff_format(fasta, 3).unwrap();
+        assert_eq!(report.format, HeaderFormat::UniParc);
+        assert_eq!(report.sampled, 3);
+        assert!((report.confidence - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(
+            report.counts,
+            vec![(HeaderFormat::UniParc, 2), (HeaderFormat::PdbSeqres, 1)]
+        );
+    }
+
+    #[test]
+    fn sniff_empty() {
+        let report = sniff_format(&b""[..], 10).unwrap();
+        assert_eq!(report.format, HeaderFormat::Unknown);
+        assert_eq!(report.confidence, 0.0);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{UniProtKB, UniProtKBIsoform};
+use std::borrow::Cow;
+
+/// Common accessors of the supported header formats
+///
+/// ```rust
+/// use uniprot_fasta_header::FastaHeader;
+///
+/// fn label<H: FastaHeader>(header: &H) -> String {
+///     format!("{}:{}", header.source_database(), header.identifier())
+/// }
+///
+/// let uniprot = uniprot_fasta_header::uniprotkb(b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1").unwrap();
+/// let ncbi = uniprot_fasta_header::ncbi(b">NP_776719.1 kappa-casein precursor [Bos taurus]").unwrap();
+///
+/// assert_eq!(label(&uniprot), "sp:P02668");
+/// assert_eq!(label(&ncbi), "refseq:NP_776719");
+/// ```
+pub trait FastaHeader {
+    /// Primary identifier: accession, isoform identifier, stable identifier, PDB chain...
+    fn identifier(&self) -> Cow<'_, str>;
+    /// Organism name
+    fn organism_name(&self) -> Option<&str>;
+    /// NCBI taxonomic identifier
+    fn taxon_id(&self) -> Option<&str>;
+    /// Gene name
+    fn gene_name(&self) -> Option<&str>;
+    /// Protein name or description, empty if there is none
+    fn description(&self) -> &str;
+    /// Source database code: `sp`, `tr`, `uniref`, `uniparc`, `refseq`, `genbank`, `ensembl`, `pdb` or `custom`
+    fn source_database(&self) -> &'static str;
+}
+
+impl FastaHeader for UniProtKB {
+    fn identifier(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.identifier)
+    }
+
+    fn organism_name(&self) -> Option<&str> {
+        Some(&self.organism_name)
+    }
+
+    fn taxon_id(&self) -> Option<&str> {
+        self.organism_identifier.as_deref()
+    }
+
+    fn gene_name(&self) -> Option<&str> {
+        self.gene_name.as_deref()
+    }
+
+    fn description(&self) -> &str {
+        &self.protein_name
+    }
+
+    fn source_database(&self) -> &'static str {
+        self.database.code()
+    }
+}
+
+impl FastaHeader for UniProtKBIsoform {
+    fn identifier(&self) -> Cow<'_, str> {
+        Cow::Owned(format!("{}-{}", self.identifier, self.isoform))
+    }
+
+    fn organism_name(&self) -> Option<&str> {
+        Some(&self.organism_name)
+    }
+
+    fn taxon_id(&self) -> Option<&str> {
+        self.organism_identifier.as_deref()
+    }
+
+    fn gene_name(&self) -> Option<&str> {
+        self.gene_name.as_deref()
+    }
+
+    fn description(&self) -> &str {
+        &self.protein_name
+    }
+
+    fn source_database(&self) -> &'static str {
+        self.database.code()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ensembl, ncbi, pdb_seqres, uniprotkb, uniprotkb_iso};
+    use pretty_assertions::assert_eq;
+
+    fn summary<H: FastaHeader>(
+        header: &H,
+    ) -> (String, Option<&str>, Option<&str>, Option<&str>, &str, &str) {
+        (
+            header.identifier().to_string(),
+            header.organism_name(),
+            header.taxon_id(),
+            header.gene_name(),
+            header.description(),
+            header.source_database(),
+        )
+    }
+
+    #[test]
+    fn uniprot() {
+        let kb = uniprotkb(
+            b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1",
+        )
+        .unwrap();
+        assert_eq!(
+            summary(&kb),
+            (
+                "P02668".to_string(),
+                Some("Bos taurus"),
+                Some("9913"),
+                Some("CSN3"),
+                "Kappa-casein",
+                "sp"
+            )
+        );
+
+        let iso = uniprotkb_iso(b">tr|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2").unwrap();
+        assert_eq!(summary(&iso).0, "Q9H553-2");
+        assert_eq!(summary(&iso).5, "tr");
+    }
+
+    #[test]
+    fn other_formats() {
+        let refseq =
+            ncbi(b">NP_000005.3 alpha-2-macroglobulin isoform a precursor [Homo sapiens]").unwrap();
+        assert_eq!(
+            summary(&refseq),
+            (
+                "NP_000005".to_string(),
+                Some("Homo sapiens"),
+                None,
+                None,
+                "alpha-2-macroglobulin isoform a precursor",
+                "refseq"
+            )
+        );
+
+        let pep = ensembl(b">ENSP00000269305.4 pep chromosome:GRCh38:17:7661779:7687538:-1 gene:ENSG00000141510.18 transcript:ENST00000269305.9 gene_symbol:TP53 description:tumor protein p53 [Source:HGNC Symbol;Acc:HGNC:11998]").unwrap();
+        assert_eq!(
+            summary(&pep),
+            (
+                "ENSP00000269305".to_string(),
+                None,
+                None,
+                Some("TP53"),
+                "tumor protein p53",
+                "ensembl"
+            )
+        );
+
+        let pdb = pdb_seqres(b">101m_A mol:protein length:154  MYOGLOBIN").unwrap();
+        assert_eq!(
+            summary(&pdb),
+            ("101m_A".to_string(), None, None, None, "MYOGLOBIN", "pdb")
+        );
+    }
+}
@@ -0,0 +1,488 @@
+use crate::{error::UniProtHeaderError, filter::trim_newline, header::FastaHeader};
+use std::{collections::HashMap, fmt, io::BufRead, str::FromStr};
+
+/// Databases of the UniProt ID mapping files
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CrossReferenceDb {
+    /// UniProtKB entry name (`UniProtKB-ID`)
+    UniProtKBId,
+    /// Gene name (`Gene_Name`)
+    GeneName,
+    /// NCBI Gene (`GeneID`)
+    GeneId,
+    /// RefSeq protein (`RefSeq`)
+    RefSeq,
+    /// RefSeq nucleotide (`RefSeq_NT`)
+    RefSeqNt,
+    /// NCBI GI number (`GI`)
+    Gi,
+    /// PDB (`PDB`)
+    Pdb,
+    /// Gene Ontology (`GO`)
+    Go,
+    /// UniRef100 cluster (`UniRef100`)
+    UniRef100,
+    /// UniRef90 cluster (`UniRef90`)
+    UniRef90,
+    /// UniRef50 cluster (`UniRef50`)
+    UniRef50,
+    /// UniParc (`UniParc`)
+    UniParc,
+    /// PIR (`PIR`)
+    Pir,
+    /// NCBI taxonomy (`NCBI_TaxID`)
+    NcbiTaxId,
+    /// OMIM (`MIM`)
+    Mim,
+    /// UniGene (`UniGene`)
+    UniGene,
+    /// PubMed (`PubMed`)
+    PubMed,
+    /// EMBL/GenBank/DDBJ (`EMBL`)
+    Embl,
+    /// EMBL/GenBank/DDBJ coding sequence (`EMBL-CDS`)
+    EmblCds,
+    /// Ensembl gene (`Ensembl`)
+    Ensembl,
+    /// Ensembl transcript (`Ensembl_TRS`)
+    EnsemblTrs,
+    /// Ensembl protein (`Ensembl_PRO`)
+    EnsemblPro,
+    /// Any other database of `idmapping.dat`
+    Other(String),
+}
+
+impl CrossReferenceDb {
+    /// Name used in `idmapping.dat`
+    pub fn name(&self) -> &str {
+        match self {
+            CrossReferenceDb::UniProtKBId => "UniProtKB-ID",
+            CrossReferenceDb::GeneName => "Gene_Name",
+            CrossReferenceDb::GeneId => "GeneID",
+            CrossReferenceDb::RefSeq => "RefSeq",
+            CrossReferenceDb::RefSeqNt => "RefSeq_NT",
+            CrossReferenceDb::Gi => "GI",
+            CrossReferenceDb::Pdb => "PDB",
+            CrossReferenceDb::Go => "GO",
+            CrossReferenceDb::UniRef100 => "UniRef100",
+            CrossReferenceDb::UniRef90 => "UniRef90",
+            CrossReferenceDb::UniRef50 => "UniRef50",
+            CrossReferenceDb::UniParc => "UniParc",
+            CrossReferenceDb::Pir => "PIR",
+            CrossReferenceDb::NcbiTaxId => "NCBI_TaxID",
+            CrossReferenceDb::Mim => "MIM",
+            CrossReferenceDb::UniGene => "UniGene",
+            CrossReferenceDb::PubMed => "PubMed",
+            CrossReferenceDb::Embl => "EMBL",
+            CrossReferenceDb::EmblCds => "EMBL-CDS",
+            CrossReferenceDb::Ensembl => "Ensembl",
+            CrossReferenceDb::EnsemblTrs => "Ensembl_TRS",
+            CrossReferenceDb::EnsemblPro => "Ensembl_PRO",
+            CrossReferenceDb::Other(name) => name,
+        }
+    }
+
+    // Columns 2 to 21 of idmapping_selected.tab, the last one is additional PubMed references
+    const SELECTED: [CrossReferenceDb; 21] = [
+        CrossReferenceDb::UniProtKBId,
+        CrossReferenceDb::GeneId,
+        CrossReferenceDb::RefSeq,
+        CrossReferenceDb::Gi,
+        CrossReferenceDb::Pdb,
+        CrossReferenceDb::Go,
+        CrossReferenceDb::UniRef100,
+        CrossReferenceDb::UniRef90,
+        CrossReferenceDb::UniRef50,
+        CrossReferenceDb::UniParc,
+        CrossReferenceDb::Pir,
+        CrossReferenceDb::NcbiTaxId,
+        CrossReferenceDb::Mim,
+        CrossReferenceDb::UniGene,
+        CrossReferenceDb::PubMed,
+        CrossReferenceDb::Embl,
+        CrossReferenceDb::EmblCds,
+        CrossReferenceDb::Ensembl,
+        CrossReferenceDb::EnsemblTrs,
+        CrossReferenceDb::EnsemblPro,
+        CrossReferenceDb::PubMed,
+    ];
+}
+
+impl FromStr for CrossReferenceDb {
+    type Err = UniProtHeaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "UniProtKB-ID" => CrossReferenceDb::UniProtKBId,
+            "Gene_Name" => CrossReferenceDb::GeneName,
+            "GeneID" => CrossReferenceDb::GeneId,
+            "RefSeq" => CrossReferenceDb::RefSeq,
+            "RefSeq_NT" => CrossReferenceDb::RefSeqNt,
+            "GI" => CrossReferenceDb::Gi,
+            "PDB" => CrossReferenceDb::Pdb,
+            "GO" => CrossReferenceDb::Go,
+            "UniRef100" => CrossReferenceDb::UniRef100,
+            "UniRef90" => CrossReferenceDb::UniRef90,
+            "UniRef50" => CrossReferenceDb::UniRef50,
+            "UniParc" => CrossReferenceDb::UniParc,
+            "PIR" => CrossReferenceDb::Pir,
+            "NCBI_TaxID" => CrossReferenceDb::NcbiTaxId,
+            "MIM" => CrossReferenceDb::Mim,
+            "UniGene" => CrossReferenceDb::UniGene,
+            "PubMed" => CrossReferenceDb::PubMed,
+            "EMBL" => CrossReferenceDb::Embl,
+            "EMBL-CDS" => CrossReferenceDb::EmblCds,
+            "Ensembl" => CrossReferenceDb::Ensembl,
+            "Ensembl_TRS" => CrossReferenceDb::EnsemblTrs,
+            "Ensembl_PRO" => CrossReferenceDb::EnsemblPro,
+            other => CrossReferenceDb::Other(other.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for CrossReferenceDb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Identifier of an entry in another database
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CrossReference {
+    /// Database
+    pub database: CrossReferenceDb,
+    /// Identifier in the database
+    pub identifier: String,
+}
+
+fn line_error(line: &[u8], message: &str, number: usize) -> UniProtHeaderError {
+    UniProtHeaderError::ParsingError(
+        String::from_utf8_lossy(line).to_string(),
+        message.to_string(),
+    )
+    .at_line(number)
+}
+
+/// Streaming reader of `idmapping.dat`, one accession and cross-reference per line
+#[derive(Debug)]
+pub struct IdMappingReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    line_number: usize,
+}
+
+impl<R: BufRead> IdMappingReader<R> {
+    /// New `idmapping.dat` reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::new(),
+            line_number: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for IdMappingReader<R> {
+    type Item = Result<(String, CrossReference), UniProtHeaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Err(e) => return Some(Err(e.into())),
+                Ok(0) => return None,
+                Ok(_) => self.line_number += 1,
+            }
+            let line = trim_newline(&self.line);
+            if line.is_empty() {
+                continue;
+            }
+
+            let columns: Vec<&[u8]> = line.split(|&c| c == b'\t').collect();
+            return Some(match columns.as_slice() {
+                [accession, database, identifier] => Ok((
+                    String::from_utf8_lossy(accession).to_string(),
+                    CrossReference {
+                        database: String::from_utf8_lossy(database).parse().unwrap(),
+                        identifier: String::from_utf8_lossy(identifier).to_string(),
+                    },
+                )),
+                _ => Err(line_error(
+                    line,
+                    "expected 3 tab separated columns",
+                    self.line_number,
+                )),
+            });
+        }
+    }
+}
+
+/// Streaming reader of `idmapping_selected.tab`, one accession and its cross-references per line
+#[derive(Debug)]
+pub struct IdMappingSelectedReader<R> {
+    reader: R,
+    line: Vec<u8>,
+    line_number: usize,
+}
+
+impl<R: BufRead> IdMappingSelectedReader<R> {
+    /// New `idmapping_selected.tab` reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: Vec::new(),
+            line_number: 0,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for IdMappingSelectedReader<R> {
+    type Item = Result<(String, Vec<CrossReference>), UniProtHeaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Err(e) => return Some(Err(e.into())),
+                Ok(0) => return None,
+                Ok(_) => self.line_number += 1,
+            }
+            let line = trim_newline(&self.line);
+            if line.is_empty() {
+                continue;
+            }
+
+            let line = String::from_utf8_lossy(line);
+            let mut columns = line.split('\t');
+            let accession = columns.next().unwrap_or_default();
+            if accession.is_empty() {
+                return Some(Err(line_error(
+                    line.as_bytes(),
+                    "missing accession",
+                    self.line_number,
+                )));
+            }
+
+            let references = columns
+                .zip(CrossReferenceDb::SELECTED.iter())
+                .flat_map(|(column, database)| {
+                    column
+                        .split("; ")
+                        .filter(|identifier| !identifier.is_empty())
+                        .map(move |identifier| CrossReference {
+                            database: database.clone(),
+                            identifier: identifier.to_string(),
+                        })
+                })
+                .collect();
+
+            return Some(Ok((accession.to_string(), references)));
+        }
+    }
+}
+
+/// Cross-references of UniProtKB accessions, indexed by accession
+///
+/// ```rust
+/// use uniprot_fasta_header::{CrossReferenceDb, IdMapping};
+///
+/// let dat: &[u8] = b"P02668\tUniProtKB-ID\tCASK_BOVIN\nP02668\tGeneID\t281111\nP02668\tRefSeq\tNP_776719.1\n";
+///
+/// let mut mapping = IdMapping::new().databases(vec![CrossReferenceDb::RefSeq, CrossReferenceDb::GeneId]);
+/// mapping.read_dat(dat).unwrap();
+///
+/// let header = uniprot_fasta_header::uniprotkb(b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1").unwrap();
+/// assert_eq!(mapping.for_header(&header).len(), 2);
+/// assert_eq!(mapping.identifiers("P02668", &CrossReferenceDb::RefSeq), vec!["NP_776719.1"]);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct IdMapping {
+    references: HashMap<String, Vec<CrossReference>>,
+    databases: Option<Vec<CrossReferenceDb>>,
+}
+
+impl IdMapping {
+    /// Empty mapping keeping every database
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only keep these databases when reading, the files are large
+    pub fn databases(mut self, databases: Vec<CrossReferenceDb>) -> Self {
+        self.databases = Some(databases);
+        self
+    }
+
+    fn keep(&self, database: &CrossReferenceDb) -> bool {
+        self.databases
+            .as_ref()
+            .is_none_or(|databases| databases.contains(database))
+    }
+
+    /// Insert a cross-reference, returns whether it was kept
+    pub fn insert(&mut self, accession: String, reference: CrossReference) -> bool {
+        if !self.keep(&reference.database) {
+            return false;
+        }
+        self.references
+            .entry(accession)
+            .or_default()
+            .push(reference);
+        true
+    }
+
+    /// Load `idmapping.dat`, returns the number of cross-references kept
+    pub fn read_dat<R: BufRead>(&mut self, reader: R) -> Result<usize, UniProtHeaderError> {
+        let mut counter = 0;
+        for row in IdMappingReader::new(reader) {
+            let (accession, reference) = row?;
+            if self.insert(accession, reference) {
+                counter += 1;
+            }
+        }
+        Ok(counter)
+    }
+
+    /// Load `idmapping_selected.tab`, returns the number of cross-references kept
+    pub fn read_selected<R: BufRead>(&mut self, reader: R) -> Result<usize, UniProtHeaderError> {
+        let mut counter = 0;
+        for row in IdMappingSelectedReader::new(reader) {
+            let (accession, references) = row?;
+            for reference in references {
+                if self.insert(accession.clone(), reference) {
+                    counter += 1;
+                }
+            }
+        }
+        Ok(counter)
+    }
+
+    /// Cross-references of an accession
+    pub fn get(&self, accession: &str) -> &[CrossReference] {
+        self.references
+            .get(accession)
+            .map_or(&[], |references| references.as_slice())
+    }
+
+    /// Identifiers of an accession in one database
+    pub fn identifiers(&self, accession: &str, database: &CrossReferenceDb) -> Vec<&str> {
+        self.get(accession)
+            .iter()
+            .filter(|reference| &reference.database == database)
+            .map(|reference| reference.identifier.as_str())
+            .collect()
+    }
+
+    /// Cross-references of a parsed header, isoforms fall back to their canonical accession
+    pub fn for_header<H: FastaHeader + ?Sized>(&self, header: &H) -> &[CrossReference] {
+        let identifier = header.identifier();
+        let references = self.get(&identifier);
+        if !references.is_empty() {
+            return references;
+        }
+        match identifier.split_once('-') {
+            Some((canonical, _)) => self.get(canonical),
+            None => references,
+        }
+    }
+
+    /// Number of accessions
+    pub fn len(&self) -> usize {
+        self.references.len()
+    }
+
+    /// Whether the mapping is empty
+    pub fn is_empty(&self) -> bool {
+        self.references.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uniprotkb_iso;
+    use pretty_assertions::assert_eq;
+
+    const DAT: &str = "P31946\tUniProtKB-ID\t1433B_HUMAN
+P31946\tGene_Name\tYWHAB
+P31946\tGeneID\t7529
+P31946\tRefSeq\tNP_003395.1
+P31946\tRefSeq\tNP_647539.1
+P31946\tPDB\t2BQ0
+P31946\tEnsembl_PRO\tENSP00000300161
+P31946\tSTRING\t9606.ENSP00000361930
+
+P31946-2\tRefSeq\tNP_647539.1
+";
+
+    #[test]
+    fn dat() {
+        let rows: Vec<_> = IdMappingReader::new(DAT.as_bytes())
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(rows.len(), 9);
+        assert_eq!(
+            rows[7].1,
+            CrossReference {
+                database: CrossReferenceDb::Other("STRING".to_string()),
+                identifier: "9606.ENSP00000361930".to_string(),
+            }
+        );
+
+        let mut mapping = IdMapping::new();
+        assert_eq!(mapping.read_dat(DAT.as_bytes()).unwrap(), 9);
+        assert_eq!(
+            mapping.identifiers("P31946", &CrossReferenceDb::RefSeq),
+            vec!["NP_003395.1", "NP_647539.1"]
+        );
+        assert!(mapping.get("P04637").is_empty());
+    }
+
+    #[test]
+    fn selected() {
+        let tab = "Q6GZX4\t001R_FRG3G\t2947773\tYP_031579.1\t81941549; 49237298\t\tGO:0006355; GO:0046782\tUniRef100_Q6GZX4\tUniRef90_Q6GZX4\tUniRef50_Q6GZX4\tUPI00003B0FD4\t\t654924\t\t\t15165820\tAY548484\tAAT09660.1\t\t\t\t\n";
+        let (accession, references) = IdMappingSelectedReader::new(tab.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(accession, "Q6GZX4");
+        assert_eq!(references.len(), 15);
+        assert_eq!(
+            references[3],
+            CrossReference {
+                database: CrossReferenceDb::Gi,
+                identifier: "81941549".to_string(),
+            }
+        );
+
+        let mut mapping = IdMapping::new().databases(vec![CrossReferenceDb::NcbiTaxId]);
+        assert_eq!(mapping.read_selected(tab.as_bytes()).unwrap(), 1);
+        assert_eq!(
+            mapping.identifiers("Q6GZX4", &CrossReferenceDb::NcbiTaxId),
+            vec!["654924"]
+        );
+    }
+
+    #[test]
+    fn isoform_header() {
+        let mut mapping = IdMapping::new();
+        mapping.read_dat(DAT.as_bytes()).unwrap();
+
+        let isoform = uniprotkb_iso(b">sp|P31946-2|1433B_HUMAN Isoform Short of 14-3-3 protein beta/alpha OS=Homo sapiens OX=9606 GN=YWHAB").unwrap();
+        assert_eq!(mapping.for_header(&isoform).len(), 1);
+
+        let other = uniprotkb_iso(b">sp|P31946-3|1433B_HUMAN Isoform 3 of 14-3-3 protein beta/alpha OS=Homo sapiens OX=9606 GN=YWHAB").unwrap();
+        assert_eq!(mapping.for_header(&other).len(), 8);
+    }
+
+    #[test]
+    fn invalid_line() {
+        let err = IdMappingReader::new(&b"P31946\tGeneID\n"[..])
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(err, UniProtHeaderError::Line(1, _)));
+    }
+}
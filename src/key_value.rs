@@ -0,0 +1,186 @@
+use crate::{
+    error::UniProtHeaderError,
+    header::FastaHeader,
+    parser::{chevron, key, key_value, space, until_key},
+};
+use nom::{
+    bytes::complete::take_till1,
+    combinator::{all_consuming, opt},
+    error::ParseError,
+    multi::many0,
+    sequence::preceded,
+    IResult,
+};
+use std::{borrow::Cow, fmt};
+
+/// Header with an identifier, a description and `KEY=value` pairs
+///
+/// `>Identifier Description KEY=value KEY=value ...`, the convention of UniProt headers used by in-house FASTA files.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct KeyValueHeader {
+    /// Identifier, the first word of the header
+    pub identifier: String,
+    /// Free text until the first `KEY=`
+    pub description: String,
+    /// `KEY=value` pairs, in the order of the header
+    pub fields: Vec<(String, String)>,
+}
+
+impl KeyValueHeader {
+    /// Value of the first pair with this key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Write the fasta header
+impl fmt::Display for KeyValueHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ">{}", self.identifier)?;
+        if !self.description.is_empty() {
+            write!(f, " {}", self.description)?;
+        }
+        for (key, value) in &self.fields {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// UniProt keys: `OS` organism name, `OX` taxon identifier and `GN` gene name
+impl FastaHeader for KeyValueHeader {
+    fn identifier(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.identifier)
+    }
+
+    fn organism_name(&self) -> Option<&str> {
+        self.get("OS")
+    }
+
+    fn taxon_id(&self) -> Option<&str> {
+        self.get("OX")
+    }
+
+    fn gene_name(&self) -> Option<&str> {
+        self.get("GN")
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn source_database(&self) -> &'static str {
+        "custom"
+    }
+}
+
+/// Parse a header made of an identifier, a description and `KEY=value` pairs
+///
+/// ```rust
+/// let header = b">seq123 heat shock protein OS=Homo sapiens OX=9606 sample=S4 condition=heat";
+///
+/// let parsed = uniprot_fasta_header::key_value_header(header).unwrap();
+///
+/// assert_eq!(parsed.identifier, "seq123");
+/// assert_eq!(parsed.description, "heat shock protein");
+/// assert_eq!(parsed.get("OS"), Some("Homo sapiens"));
+/// assert_eq!(parsed.get("condition"), Some("heat"));
+/// ```
+pub fn key_value_header(string: &[u8]) -> Result<KeyValueHeader, UniProtHeaderError> {
+    match parse_key_value_header(string) {
+        Ok((_, parsed)) => Ok(parsed),
+        Err(err) => match err {
+            nom::Err::Incomplete(_i) => Err(UniProtHeaderError::Incomplete),
+            nom::Err::Error((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+            nom::Err::Failure((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+        },
+    }
+}
+
+// Description until the first key, empty if the header goes straight to the pairs
+fn description(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    if key(input).is_ok() {
+        Ok((input, &input[..0]))
+    } else {
+        until_key(input)
+    }
+}
+
+fn to_string(input: &[u8]) -> String {
+    String::from_utf8_lossy(input).trim().to_string()
+}
+
+fn parse_key_value_header(input: &[u8]) -> IResult<&[u8], KeyValueHeader> {
+    let (input, _) = chevron(input)?;
+    let (input, identifier) = take_till1(|c| c == b' ' || c == b'\t')(input)?;
+    let (input, description) = opt(preceded(space, description))(input)?;
+    let (input, fields) = all_consuming(many0(preceded(opt(space), key_value)))(input)?;
+
+    Ok((
+        input,
+        KeyValueHeader {
+            identifier: to_string(identifier),
+            description: description.map(to_string).unwrap_or_default(),
+            fields: fields
+                .into_iter()
+                .map(|(k, v)| (to_string(k), to_string(v)))
+                .collect(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_key_value_lab_header() {
+        let entry = KeyValueHeader {
+            identifier: "seq123".to_string(),
+            description: String::new(),
+            fields: vec![
+                ("OS".to_string(), "Bos taurus".to_string()),
+                ("OX".to_string(), "9913".to_string()),
+                ("sample".to_string(), "S4".to_string()),
+                ("condition".to_string(), "heat".to_string()),
+            ],
+        };
+        let test_header = ">seq123 OS=Bos taurus OX=9913 sample=S4 condition=heat";
+        let parsed = key_value_header(test_header.as_bytes()).unwrap();
+        assert_eq!(parsed, entry);
+        assert_eq!(parsed.to_string(), test_header);
+        assert_eq!(parsed.taxon_id(), Some("9913"));
+    }
+
+    #[test]
+    fn test_key_value_uniprot_header() {
+        let parsed = key_value_header(
+            b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1",
+        )
+        .unwrap();
+        assert_eq!(parsed.identifier, "sp|P02668|CASK_BOVIN");
+        assert_eq!(parsed.description, "Kappa-casein");
+        assert_eq!(parsed.gene_name(), Some("CSN3"));
+        assert_eq!(parsed.get("SV"), Some("1"));
+    }
+
+    #[test]
+    fn test_key_value_identifier_only() {
+        let parsed = key_value_header(b">seq1").unwrap();
+        assert_eq!(parsed.description, "");
+        assert!(parsed.fields.is_empty());
+        assert!(key_value_header(b">").is_err());
+    }
+
+    #[test]
+    fn test_key_value_duplicate_keys() {
+        let parsed = key_value_header(b">seq1 desc tag=a tag=b").unwrap();
+        assert_eq!(parsed.get("tag"), Some("a"));
+        assert_eq!(parsed.fields.len(), 2);
+    }
+}
@@ -6,6 +6,8 @@
 //!
 //! `>db|UniqueIdentifier|EntryName ProteinName OS=OrganismName OX=OrganismIdentifier [GN=GeneName ]PE=ProteinExistence SV=SequenceVersion`
 //!
+//! Headers of older releases have no `OX=OrganismIdentifier`, they are detected automatically (see [`HeaderLayout`]).
+//!
 //!
 //! ### Usage
 //!
@@ -20,7 +22,7 @@
 //!     entry_name: "YPFU_ECOLI".to_string(),
 //!     protein_name: "Uncharacterized protein in traD-traI intergenic region".to_string(),
 //!     organism_name: "Escherichia coli (strain K12)".to_string(),
-//!     organism_identifier: "83333".to_string(),
+//!     organism_identifier: Some("83333".to_string()),
 //!     gene_name: None,
 //!     protein_existence: uniprot_fasta_header::ProteinExistence::InferredHomology,
 //!     sequence_version: "1".to_string(),
@@ -50,13 +52,87 @@
 //!     entry_name: "1433B_MACFA".to_string(),
 //!     protein_name: "Isoform Short of 14-3-3 protein beta/alpha".to_string(),
 //!     organism_name: "Macaca fascicularis".to_string(),
-//!     organism_identifier: "9541".to_string(),
+//!     organism_identifier: Some("9541".to_string()),
 //!     gene_name: Some("YWHAB".to_string()),
 //! };
 //!
 //! assert_eq!(parsed, expected);
 //! ```
 //!
+//! ## NCBI
+//!
+//! ### Format
+//!
+//! `>Accession.Version Description [OrganismName]`, non-redundant (nr) headers merge several definitions separated by `\x01`.
+//!
+//! ### Usage
+//!
+//! ```rust
+//! let header = ">WP_003131952.1 30S ribosomal protein S18 [Lactococcus lactis]\x01CAA45301.1 ribosomal protein S18 [Lactococcus lactis]".as_bytes();
+//!
+//! let parsed = uniprot_fasta_header::ncbi(header).unwrap();
+//!
+//! assert_eq!(parsed.versioned_accession(), "WP_003131952.1");
+//! assert_eq!(parsed.merged[0].accession, "CAA45301");
+//! ```
+//!
+//! ## Ensembl
+//!
+//! ### Format
+//!
+//! `>ProteinID pep Location gene:GeneID transcript:TranscriptID [key:value ...] description:Description [Source:Name;Acc:Accession]`
+//!
+//! ### Usage
+//!
+//! ```rust
+//! let header = ">ENSP00000269305.4 pep chromosome:GRCh38:17:7661779:7687538:-1 gene:ENSG00000141510.18 transcript:ENST00000269305.9 gene_biotype:protein_coding transcript_biotype:protein_coding gene_symbol:TP53 description:tumor protein p53 [Source:HGNC Symbol;Acc:HGNC:11998]".as_bytes();
+//!
+//! let parsed = uniprot_fasta_header::ensembl(header).unwrap();
+//!
+//! assert_eq!(parsed.transcript.to_string(), "ENST00000269305.9");
+//! assert_eq!(parsed.location.start, 7661779);
+//! assert_eq!(parsed.gene_symbol, Some("TP53".to_string()));
+//! ```
+//!
+//! ## PDB SEQRES
+//!
+//! ### Format
+//!
+//! `>PdbID_Chain mol:MoleculeType length:Length  Name`
+//!
+//! ### Usage
+//!
+//! ```rust
+//! let header = ">101m_A mol:protein length:154  MYOGLOBIN".as_bytes();
+//!
+//! let parsed = uniprot_fasta_header::pdb_seqres(header).unwrap();
+//!
+//! let expected = uniprot_fasta_header::PdbSeqres {
+//!     pdb_id: "101m".to_string(),
+//!     chain: "A".to_string(),
+//!     molecule: uniprot_fasta_header::MoleculeType::Protein,
+//!     length: 154,
+//!     name: "MYOGLOBIN".to_string(),
+//! };
+//!
+//! assert_eq!(parsed, expected);
+//! ```
+//!
+//! ## Any format
+//!
+//! UniRef and UniParc headers are parsed with [`uniref`] and [`uniparc`]. When the source of a file is unknown,
+//! [`parse_any`] tries every format, [`detect_format`] reports the format of one header and [`sniff_format`]
+//! the dominant format of the first headers of a file.
+//!
+//! ```rust
+//! use uniprot_fasta_header::FastaHeader;
+//!
+//! let parsed = uniprot_fasta_header::parse_any(b">UPI0000000001 status=active").unwrap();
+//!
+//! assert_eq!(parsed.format(), uniprot_fasta_header::HeaderFormat::UniParc);
+//! assert_eq!(parsed.source_database(), "uniparc");
+//! ```
+//!
 //! ## Reference
 //!
 //! - [UniProt Fasta header help page](https://www.uniprot.org/help/fasta-headers)
@@ -67,18 +143,85 @@
 #[macro_use]
 extern crate serde;
 
+mod accession;
+mod checksum;
+mod decoy;
+mod diff;
+mod ensembl;
 mod error;
+mod fasta;
+mod filter;
+mod flat_file;
+mod format;
+mod header;
+mod idmapping;
+mod key_value;
+mod mmap;
+mod ncbi;
+#[cfg(feature = "rayon")]
+mod parallel;
 mod parser;
+mod pdb_seqres;
+mod rewrite;
+mod short_id;
+mod speclist;
+mod stats;
+mod stream;
+#[cfg(feature = "taxonomy")]
+mod taxonomy;
+mod tsv;
+mod uniparc;
 mod uniprotkb;
 mod uniprotkb_isoform;
+mod uniref;
+#[cfg(feature = "quick-xml")]
+mod xml;
 
-pub use uniprotkb::uniprotkb;
+pub use accession::{AccessionResolver, Resolution};
+pub use checksum::{check_crc64, crc64, md5, ChecksumCheck, ChecksumValidator};
+pub use decoy::{write_decoys, DecoyGenerator, DecoyMethod, TagPrefixes, Tagged};
+pub use diff::{diff_fasta, Change, ChangeKind, ReleaseDiff};
+pub use ensembl::{ensembl, DescriptionSource, Ensembl, GenomicLocation, StableId};
+pub use error::UniProtHeaderError;
+pub use fasta::{FastaReader, Record, DEFAULT_LINE_WIDTH};
+pub use filter::{filter_fasta, Expression, Field, Filter, Filterable, Operator};
+pub use flat_file::{uniprot_entry, EntryReader, Gene, UniProtEntry};
+pub use format::{detect_format, parse_any, sniff_format, FormatReport, Header, HeaderFormat};
+pub use header::FastaHeader;
+pub use idmapping::{
+    CrossReference, CrossReferenceDb, IdMapping, IdMappingReader, IdMappingSelectedReader,
+};
+pub use key_value::{key_value_header, KeyValueHeader};
+#[cfg(feature = "memmap2")]
+pub use mmap::MappedFasta;
+pub use mmap::{header_lines, HeaderLines};
+pub use ncbi::{ncbi, Ncbi, NcbiDefinition};
+#[cfg(feature = "rayon")]
+pub use parallel::{parse_bytes_par, parse_lines_par, parse_reader_par, DEFAULT_CHUNK_SIZE};
+pub use pdb_seqres::{pdb_seqres, PdbSeqres};
+pub use rewrite::{rewrite_fasta, HeaderMapping, Rewriter, Template};
+pub use short_id::{shorten_fasta, ShortIdGenerator, ShortIdSource, PHYLIP_NAME_LENGTH};
+pub use speclist::{EntryName, Kingdom, Species, SpeciesCheck, SpeciesList};
+pub use stats::{
+    summarize_fasta, SequenceStats, SequenceSummary, StatsSummary, NON_STANDARD_RESIDUES,
+};
+#[cfg(feature = "tokio")]
+pub use stream::AsyncHeaderReader;
+pub use stream::StreamParser;
+#[cfg(feature = "taxonomy")]
+pub use taxonomy::{filter_fasta_by_taxon, Taxonomy};
+pub use tsv::{TsvReader, TsvRow, TsvTable};
+pub use uniparc::{uniparc, UniParc};
 pub use uniprotkb::UniProtKB;
-pub use uniprotkb_isoform::uniprotkb_iso;
+pub use uniprotkb::{uniprotkb, uniprotkb_with_layout};
 pub use uniprotkb_isoform::UniProtKBIsoform;
+pub use uniprotkb_isoform::{uniprotkb_iso, uniprotkb_iso_with_layout};
+pub use uniref::{uniref, UniRef};
+#[cfg(feature = "quick-xml")]
+pub use xml::{XmlEntry, XmlReader};
 
 /// UniProtKB database
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Database {
     /// UniProtKB/Swiss-Prot
@@ -87,10 +230,66 @@ pub enum Database {
     TrEMBL,
 }
 
+impl Database {
+    /// Database code used in fasta headers, `sp` or `tr`
+    pub fn code(&self) -> &'static str {
+        match self {
+            Database::SwissProt => "sp",
+            Database::TrEMBL => "tr",
+        }
+    }
+}
+
+/// Molecule type of a PDB chain
+#[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MoleculeType {
+    /// Protein
+    Protein,
+    /// Nucleic acid
+    NucleicAcid,
+}
+
+impl MoleculeType {
+    /// Molecule type code used in `pdb_seqres.txt` headers, `protein` or `na`
+    pub fn code(&self) -> &'static str {
+        match self {
+            MoleculeType::Protein => "protein",
+            MoleculeType::NucleicAcid => "na",
+        }
+    }
+}
+
+/// Layout of the UniProtKB headers
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum HeaderLayout {
+    /// Current headers, with the NCBI taxonomic identifier (`OX=`)
+    Current,
+    /// Headers of older releases, without `OX=`
+    ///
+    /// `>sp|P12345|AATM_RABIT Aspartate aminotransferase OS=Oryctolagus cuniculus GN=GOT2 PE=1 SV=2`
+    Legacy,
+    /// Legacy if the header has no `OX=`, current otherwise
+    Auto,
+}
+
+impl HeaderLayout {
+    // Resolve `Auto` for a header
+    fn resolve(self, header: &[u8]) -> HeaderLayout {
+        match self {
+            HeaderLayout::Auto => match memchr::memmem::find(header, b" OX=") {
+                Some(_) => HeaderLayout::Current,
+                None => HeaderLayout::Legacy,
+            },
+            layout => layout,
+        }
+    }
+}
+
 /// Protein Existence types
 ///
 /// See [Protein existence](https://www.uniprot.org/help/protein%5Fexistence).
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ProteinExistence {
     /// 1. Experimental evidence at protein level
@@ -104,3 +303,42 @@ pub enum ProteinExistence {
     /// 5. Protein uncertain
     Uncertain,
 }
+
+impl ProteinExistence {
+    /// Protein existence level, from 1 to 5
+    pub fn level(&self) -> u8 {
+        match self {
+            ProteinExistence::ExperimentalEvidenceProtein => 1,
+            ProteinExistence::ExperimentalEvidenceTranscript => 2,
+            ProteinExistence::InferredHomology => 3,
+            ProteinExistence::Predicted => 4,
+            ProteinExistence::Uncertain => 5,
+        }
+    }
+
+    /// Protein existence of a level, from 1 to 5
+    pub fn from_level(level: u8) -> Option<Self> {
+        match level {
+            1 => Some(ProteinExistence::ExperimentalEvidenceProtein),
+            2 => Some(ProteinExistence::ExperimentalEvidenceTranscript),
+            3 => Some(ProteinExistence::InferredHomology),
+            4 => Some(ProteinExistence::Predicted),
+            5 => Some(ProteinExistence::Uncertain),
+            _ => None,
+        }
+    }
+
+    /// Protein existence of its evidence name, case-insensitive (`Evidence at protein level`)
+    pub fn from_evidence(evidence: &str) -> Option<Self> {
+        match evidence.to_ascii_lowercase().as_str() {
+            "evidence at protein level" => Some(ProteinExistence::ExperimentalEvidenceProtein),
+            "evidence at transcript level" => {
+                Some(ProteinExistence::ExperimentalEvidenceTranscript)
+            }
+            "inferred from homology" => Some(ProteinExistence::InferredHomology),
+            "predicted" => Some(ProteinExistence::Predicted),
+            "uncertain" => Some(ProteinExistence::Uncertain),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::filter::trim_newline;
+use memchr::{memchr, memmem};
+
+#[cfg(feature = "memmap2")]
+use crate::error::UniProtHeaderError;
+#[cfg(feature = "memmap2")]
+use memmap2::Mmap;
+#[cfg(feature = "memmap2")]
+use std::{fs::File, path::Path};
+
+/// Iterator over the header lines of a FASTA buffer
+///
+/// Lines are borrowed from the buffer, without the trailing newline, and still start with `>`.
+///
+/// ```rust
+/// let fasta = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKS\n";
+///
+/// for header in uniprot_fasta_header::header_lines(fasta) {
+///     assert!(uniprot_fasta_header::uniprotkb(header).is_ok());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct HeaderLines<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+/// Header lines of a FASTA buffer
+pub fn header_lines(input: &[u8]) -> HeaderLines<'_> {
+    HeaderLines { input, position: 0 }
+}
+
+impl<'a> Iterator for HeaderLines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.input.get(self.position..)?;
+
+        let start = if self.position == 0 && rest.starts_with(b">") {
+            0
+        } else {
+            memmem::find(rest, b"\n>")? + 1
+        };
+        let line = &rest[start..];
+        let end = memchr(b'\n', line).unwrap_or(line.len());
+
+        self.position += start + end;
+        Some(trim_newline(&line[..end]))
+    }
+}
+
+/// Memory-mapped FASTA file
+///
+/// The file is not read nor copied in memory: header lines are found with a byte search and borrowed from the mapping.
+///
+/// ```no_run
+/// let fasta = uniprot_fasta_header::MappedFasta::open("uniprot_trembl.fasta").unwrap();
+///
+/// let parsed = fasta
+///     .headers()
+///     .filter_map(|header| uniprot_fasta_header::uniprotkb(header).ok())
+///     .count();
+/// ```
+#[cfg(feature = "memmap2")]
+#[derive(Debug)]
+pub struct MappedFasta {
+    mmap: Mmap,
+}
+
+#[cfg(feature = "memmap2")]
+impl MappedFasta {
+    /// Map a FASTA file in memory
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, UniProtHeaderError> {
+        let file = File::open(path)?;
+        // Safety: the mapping is read only. Like any memory map, modifying the file while it is mapped is undefined behaviour.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self { mmap })
+    }
+
+    /// Content of the file
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.mmap
+    }
+
+    /// Header lines of the file
+    pub fn headers(&self) -> HeaderLines<'_> {
+        header_lines(&self.mmap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case(b">a\nAAA\n>b\r\nBBB\nB\n>c", vec![b">a", b">b", b">c"] ; "unix and windows")]
+    #[test_case(b"\n>a\n>b\n", vec![b">a", b">b"] ; "leading empty line")]
+    #[test_case(b"AAA\n>a x\n", vec![b">a x"] ; "leading sequence")]
+    #[test_case(b"", vec![] ; "empty")]
+    fn headers(input: &[u8], expected: Vec<&[u8]>) {
+        pretty_assertions::assert_eq!(header_lines(input).collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn sequence_with_chevron() {
+        // a > inside a line is not a header
+        let input = b">a\nAA>A\n>b\n";
+        assert_eq!(
+            header_lines(input).collect::<Vec<_>>(),
+            vec![&b">a"[..], &b">b"[..]]
+        );
+    }
+}
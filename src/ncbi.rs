@@ -0,0 +1,325 @@
+use crate::{
+    error::UniProtHeaderError,
+    filter::{Field, Filterable},
+    header::FastaHeader,
+    parser::{chevron, ncbi_definition, ncbi_id, space},
+};
+use nom::{
+    bytes::complete::tag,
+    combinator::{all_consuming, opt},
+    error::ParseError,
+    multi::many0,
+    sequence::{pair, preceded},
+    IResult,
+};
+use std::{borrow::Cow, fmt};
+
+/// One definition of an NCBI header: accession, description and organism
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NcbiDefinition {
+    /// Legacy GenInfo identifier (`4557225` in `gi|4557225|ref|NP_000005.2|`)
+    pub gi: Option<String>,
+    /// Database tag after the GenInfo identifier (`ref`, `gb`, `emb`, ...)
+    pub gi_database: Option<String>,
+    /// Accession without version (`NP_000005`)
+    pub accession: String,
+    /// Accession version (`3` in `NP_000005.3`)
+    pub version: Option<String>,
+    /// Description
+    pub description: String,
+    /// Organism name, between brackets at the end of the definition
+    pub organism_name: Option<String>,
+}
+
+/// NCBI RefSeq or GenBank protein header
+///
+/// `>Accession.Version Description [OrganismName]`, the non-redundant (nr) database merges identical sequences
+/// and separates their definitions with `\x01`. Accessions are RefSeq (`NP_000005`) or GenBank (`AAB59406`)
+/// protein accessions, optionally after a legacy GenInfo prefix (`gi|4557225|ref|NP_000005.2|`).
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ncbi {
+    /// Legacy GenInfo identifier (`4557225` in `gi|4557225|ref|NP_000005.2|`)
+    pub gi: Option<String>,
+    /// Database tag after the GenInfo identifier (`ref`, `gb`, `emb`, ...)
+    pub gi_database: Option<String>,
+    /// Accession without version (`NP_000005`)
+    pub accession: String,
+    /// Accession version (`3` in `NP_000005.3`)
+    pub version: Option<String>,
+    /// Description
+    pub description: String,
+    /// Organism name, between brackets at the end of the header
+    pub organism_name: Option<String>,
+    /// Other definitions merged in the same nr entry
+    pub merged: Vec<NcbiDefinition>,
+}
+
+impl Ncbi {
+    /// Accession with its version (`NP_000005.3`)
+    pub fn versioned_accession(&self) -> String {
+        versioned(&self.accession, &self.version)
+    }
+
+    /// RefSeq accessions have a two letters prefix and an underscore (`NP_`, `XP_`, `WP_`, ...)
+    pub fn is_refseq(&self) -> bool {
+        let bytes = self.accession.as_bytes();
+        bytes.len() > 3 && bytes[2] == b'_' && bytes[..2].iter().all(u8::is_ascii_uppercase)
+    }
+}
+
+fn versioned(accession: &str, version: &Option<String>) -> String {
+    match version {
+        Some(version) => format!("{}.{}", accession, version),
+        None => accession.to_string(),
+    }
+}
+
+impl fmt::Display for NcbiDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.gi, &self.gi_database) {
+            (Some(gi), Some(database)) => write!(
+                f,
+                "gi|{}|{}|{}|",
+                gi,
+                database,
+                versioned(&self.accession, &self.version)
+            )?,
+            _ => write!(f, "{}", versioned(&self.accession, &self.version))?,
+        }
+        if !self.description.is_empty() {
+            write!(f, " {}", self.description)?;
+        }
+        if let Some(organism_name) = &self.organism_name {
+            write!(f, " [{}]", organism_name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Write the NCBI fasta header
+impl fmt::Display for Ncbi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let first = NcbiDefinition {
+            gi: self.gi.clone(),
+            gi_database: self.gi_database.clone(),
+            accession: self.accession.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            organism_name: self.organism_name.clone(),
+        };
+        write!(f, ">{}", first)?;
+        for definition in &self.merged {
+            write!(f, "\x01{}", definition)?;
+        }
+        Ok(())
+    }
+}
+
+impl Filterable for Ncbi {
+    fn field(&self, field: Field) -> Option<Cow<'_, str>> {
+        match field {
+            Field::Database => Some(Cow::Borrowed(self.source_database())),
+            Field::Accession => Some(Cow::Borrowed(&self.accession)),
+            Field::ProteinName => Some(Cow::Borrowed(&self.description)),
+            Field::OrganismName => self.organism_name.as_deref().map(Cow::Borrowed),
+            Field::SequenceVersion => self.version.as_deref().map(Cow::Borrowed),
+            Field::Fragment => {
+                if self.description.contains("partial") {
+                    Some(Cow::Borrowed("true"))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FastaHeader for Ncbi {
+    fn identifier(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.accession)
+    }
+
+    fn organism_name(&self) -> Option<&str> {
+        self.organism_name.as_deref()
+    }
+
+    fn taxon_id(&self) -> Option<&str> {
+        None
+    }
+
+    fn gene_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn source_database(&self) -> &'static str {
+        if self.is_refseq() {
+            "refseq"
+        } else {
+            "genbank"
+        }
+    }
+}
+
+/// Parse an NCBI RefSeq or GenBank protein fasta header
+///
+/// ```rust
+/// let header = b">NP_000005.3 alpha-2-macroglobulin isoform a precursor [Homo sapiens]";
+///
+/// let parsed = uniprot_fasta_header::ncbi(header).unwrap();
+///
+/// assert_eq!(parsed.accession, "NP_000005");
+/// assert_eq!(parsed.version, Some("3".to_string()));
+/// assert_eq!(parsed.description, "alpha-2-macroglobulin isoform a precursor");
+/// assert_eq!(parsed.organism_name, Some("Homo sapiens".to_string()));
+/// ```
+pub fn ncbi(string: &[u8]) -> Result<Ncbi, UniProtHeaderError> {
+    match parse_ncbi(string) {
+        Ok((_, parsed)) => Ok(parsed),
+        Err(err) => match err {
+            nom::Err::Incomplete(_i) => Err(UniProtHeaderError::Incomplete),
+            nom::Err::Error((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+            nom::Err::Failure((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+        },
+    }
+}
+
+fn definition(input: &[u8]) -> IResult<&[u8], NcbiDefinition> {
+    let (input, (gi, accession, version)) = ncbi_id(input)?;
+    let (input, rest) = opt(preceded(space, ncbi_definition))(input)?;
+    let (description, organism) = rest.unwrap_or((&b""[..], None));
+
+    Ok((
+        input,
+        NcbiDefinition {
+            gi: gi.map(|(gi, _)| String::from_utf8_lossy(gi).to_string()),
+            gi_database: gi.map(|(_, database)| String::from_utf8_lossy(database).to_string()),
+            accession: String::from_utf8_lossy(accession).to_string(),
+            version: version.map(|v| String::from_utf8_lossy(v).to_string()),
+            description: String::from_utf8_lossy(description).trim().to_string(),
+            organism_name: organism.map(|o| String::from_utf8_lossy(o).trim().to_string()),
+        },
+    ))
+}
+
+fn parse_ncbi(input: &[u8]) -> IResult<&[u8], Ncbi> {
+    let (input, _) = chevron(input)?;
+    let (input, (first, merged)) =
+        all_consuming(pair(definition, many0(preceded(tag("\x01"), definition))))(input)?;
+
+    Ok((
+        input,
+        Ncbi {
+            gi: first.gi,
+            gi_database: first.gi_database,
+            accession: first.accession,
+            version: first.version,
+            description: first.description,
+            organism_name: first.organism_name,
+            merged,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_ncbi_np_000005() {
+        let entry = Ncbi {
+            gi: None,
+            gi_database: None,
+            accession: "NP_000005".to_string(),
+            version: Some("3".to_string()),
+            description: "alpha-2-macroglobulin isoform a precursor".to_string(),
+            organism_name: Some("Homo sapiens".to_string()),
+            merged: vec![],
+        };
+        let test_header = ">NP_000005.3 alpha-2-macroglobulin isoform a precursor [Homo sapiens]";
+        let parsed = ncbi(test_header.as_bytes()).unwrap();
+        assert_eq!(parsed, entry);
+        assert!(parsed.is_refseq());
+        assert_eq!(parsed.to_string(), test_header);
+    }
+
+    #[test]
+    fn test_ncbi_nr() {
+        let test_header = ">WP_003131952.1 30S ribosomal protein S18 [Lactococcus lactis]\x01WP_012898302.1 30S ribosomal protein S18 [Lactococcus lactis subsp. lactis]\x01CAA45301.1 ribosomal protein S18 [Lactococcus lactis]";
+        let parsed = ncbi(test_header.as_bytes()).unwrap();
+        assert_eq!(parsed.versioned_accession(), "WP_003131952.1");
+        assert_eq!(
+            parsed.merged,
+            vec![
+                NcbiDefinition {
+                    gi: None,
+                    gi_database: None,
+                    accession: "WP_012898302".to_string(),
+                    version: Some("1".to_string()),
+                    description: "30S ribosomal protein S18".to_string(),
+                    organism_name: Some("Lactococcus lactis subsp. lactis".to_string()),
+                },
+                NcbiDefinition {
+                    gi: None,
+                    gi_database: None,
+                    accession: "CAA45301".to_string(),
+                    version: Some("1".to_string()),
+                    description: "ribosomal protein S18".to_string(),
+                    organism_name: Some("Lactococcus lactis".to_string()),
+                },
+            ]
+        );
+        assert_eq!(parsed.to_string(), test_header);
+    }
+
+    #[test]
+    fn test_ncbi_no_organism() {
+        let parsed = ncbi(b">AAB59406 hypothetical protein").unwrap();
+        assert_eq!(parsed.version, None);
+        assert_eq!(parsed.description, "hypothetical protein");
+        assert_eq!(parsed.organism_name, None);
+        assert!(!parsed.is_refseq());
+    }
+
+    #[test]
+    fn test_ncbi_brackets_in_description() {
+        let parsed =
+            ncbi(b">XP_011518520.1 protein [NAD(P)H] reductase [Escherichia coli O157:H7]")
+                .unwrap();
+        assert_eq!(parsed.description, "protein [NAD(P)H] reductase");
+        assert_eq!(
+            parsed.organism_name,
+            Some("Escherichia coli O157:H7".to_string())
+        );
+    }
+
+    #[test]
+    fn test_ncbi_not_uniprot() {
+        assert!(ncbi(b">sp|P02668|CASK_BOVIN Kappa-casein").is_err());
+    }
+
+    #[test]
+    fn test_ncbi_not_an_accession() {
+        assert!(ncbi(b">foo").is_err());
+        assert!(ncbi(b">seq1 OS=Homo sapiens OX=9606").is_err());
+    }
+
+    #[test]
+    fn test_ncbi_geninfo() {
+        let test_header = ">gi|4557225|ref|NP_000005.2| alpha-2-macroglobulin precursor [Homo sapiens]\x01gi|224589|gb|AAB59406.1| hypothetical protein";
+        let parsed = ncbi(test_header.as_bytes()).unwrap();
+        assert_eq!(parsed.gi.as_deref(), Some("4557225"));
+        assert_eq!(parsed.gi_database.as_deref(), Some("ref"));
+        assert_eq!(parsed.versioned_accession(), "NP_000005.2");
+        assert_eq!(parsed.description, "alpha-2-macroglobulin precursor");
+        assert_eq!(parsed.merged[0].gi_database.as_deref(), Some("gb"));
+        assert_eq!(parsed.to_string(), test_header);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{error::UniProtHeaderError, filter::trim_newline};
+use rayon::prelude::*;
+use std::io::Read;
+
+/// Default number of bytes read at once by [`parse_reader_par`]
+pub const DEFAULT_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Parse lines in parallel
+///
+/// Every line is given to the parser, errors carry the line number (starting at 1). Results are in input order.
+///
+/// ```rust
+/// let lines = vec![
+///     ">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1",
+///     ">sp|P02668|CASK_BOVIN Kappa-casein",
+/// ];
+///
+/// let parsed = uniprot_fasta_header::parse_lines_par(&lines, uniprot_fasta_header::uniprotkb);
+///
+/// assert!(parsed[0].is_ok());
+/// assert!(matches!(parsed[1], Err(uniprot_fasta_header::UniProtHeaderError::Line(2, _))));
+/// ```
+pub fn parse_lines_par<S, T, F>(lines: &[S], parser: F) -> Vec<Result<T, UniProtHeaderError>>
+where
+    S: AsRef<[u8]> + Sync,
+    T: Send,
+    F: Fn(&[u8]) -> Result<T, UniProtHeaderError> + Sync,
+{
+    lines
+        .par_iter()
+        .enumerate()
+        .map(|(i, line)| parser(trim_newline(line.as_ref())).map_err(|e| e.at_line(i + 1)))
+        .collect()
+}
+
+// Header lines of a FASTA buffer with their line number
+fn header_lines(input: &[u8], first_line: usize) -> (Vec<(usize, &[u8])>, usize) {
+    let mut headers = Vec::new();
+    let mut line_number = first_line;
+
+    for line in input.split(|&c| c == b'\n') {
+        if line.starts_with(b">") {
+            headers.push((line_number, trim_newline(line)));
+        }
+        line_number += 1;
+    }
+
+    // split yields an empty slice after a final newline
+    if input.ends_with(b"\n") {
+        line_number -= 1;
+    }
+
+    (headers, line_number)
+}
+
+fn parse_headers_par<T, F>(
+    headers: &[(usize, &[u8])],
+    parser: &F,
+) -> Vec<Result<T, UniProtHeaderError>>
+where
+    T: Send,
+    F: Fn(&[u8]) -> Result<T, UniProtHeaderError> + Sync,
+{
+    headers
+        .par_iter()
+        .map(|(n, header)| parser(header).map_err(|e| e.at_line(*n)))
+        .collect()
+}
+
+/// Parse the headers of an in-memory FASTA file in parallel
+///
+/// Only lines starting with `>` are parsed. Results are in input order and errors carry the line number.
+pub fn parse_bytes_par<T, F>(input: &[u8], parser: F) -> Vec<Result<T, UniProtHeaderError>>
+where
+    T: Send,
+    F: Fn(&[u8]) -> Result<T, UniProtHeaderError> + Sync,
+{
+    let (headers, _) = header_lines(input, 1);
+    parse_headers_par(&headers, &parser)
+}
+
+// Read until the buffer holds `chunk_size` bytes, or `chunk_size` more bytes when it is already full,
+// short reads from pipes and sockets are retried. Returns whether the end of the reader was reached.
+fn fill_chunk<R: Read>(
+    reader: &mut R,
+    buffer: &mut Vec<u8>,
+    chunk_size: usize,
+) -> Result<bool, UniProtHeaderError> {
+    let wanted = match chunk_size.checked_sub(buffer.len()) {
+        Some(missing) if missing > 0 => missing,
+        _ => chunk_size,
+    };
+    let read = reader.take(wanted as u64).read_to_end(buffer)?;
+    Ok(read < wanted)
+}
+
+/// Parse the headers of a FASTA file in parallel, one chunk at a time
+///
+/// The reader is consumed in chunks of about `chunk_size` bytes, cut at line boundaries, so that the input is never
+/// loaded whole. The parsed headers of every chunk are collected in the returned `Vec`, which grows with the input.
+/// Only lines starting with `>` are parsed. Results are in input order and errors carry the line number.
+pub fn parse_reader_par<R, T, F>(
+    mut reader: R,
+    chunk_size: usize,
+    parser: F,
+) -> Result<Vec<Result<T, UniProtHeaderError>>, UniProtHeaderError>
+where
+    R: Read,
+    T: Send,
+    F: Fn(&[u8]) -> Result<T, UniProtHeaderError> + Sync,
+{
+    let chunk_size = chunk_size.max(1);
+    let mut results = Vec::new();
+    let mut buffer: Vec<u8> = Vec::with_capacity(chunk_size);
+    let mut line_number = 1;
+
+    loop {
+        let eof = fill_chunk(&mut reader, &mut buffer, chunk_size)?;
+
+        let end = if eof {
+            buffer.len()
+        } else {
+            match buffer.iter().rposition(|&c| c == b'\n') {
+                Some(i) => i + 1,
+                // No complete line yet, keep reading
+                None => continue,
+            }
+        };
+
+        let (headers, next_line) = header_lines(&buffer[..end], line_number);
+        results.extend(parse_headers_par(&headers, &parser));
+        line_number = next_line;
+        buffer.drain(..end);
+
+        if eof {
+            break;
+        }
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uniprotkb, uniprotkb_iso};
+    use pretty_assertions::assert_eq;
+
+    const FASTA: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKS\nFLLV\n>sp|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2\nMAEE\n";
+
+    #[test]
+    fn line_numbers() {
+        let parsed = parse_bytes_par(FASTA, uniprotkb);
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed[0].is_ok());
+        assert!(matches!(parsed[1], Err(UniProtHeaderError::Line(4, _))));
+
+        let parsed = parse_bytes_par(FASTA, uniprotkb_iso);
+        assert!(matches!(parsed[0], Err(UniProtHeaderError::Line(1, _))));
+        assert!(parsed[1].is_ok());
+    }
+
+    #[test]
+    fn chunks() {
+        // Chunks smaller than a line
+        for chunk_size in &[1, 7, 50, 1000] {
+            let parsed = parse_reader_par(FASTA, *chunk_size, uniprotkb).unwrap();
+            assert_eq!(parsed.len(), 2);
+            assert!(parsed[0].is_ok());
+            assert!(matches!(parsed[1], Err(UniProtHeaderError::Line(4, _))));
+        }
+    }
+
+    // Reader returning one byte per call, like a slow pipe
+    struct OneByte<'a>(&'a [u8]);
+
+    impl Read for OneByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match (self.0.split_first(), buf.first_mut()) {
+                (Some((&c, rest)), Some(first)) => {
+                    *first = c;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn short_reads() {
+        let mut buffer = Vec::new();
+        assert!(!fill_chunk(&mut OneByte(FASTA), &mut buffer, 50).unwrap());
+        assert_eq!(buffer.len(), 50);
+
+        let parsed = parse_reader_par(OneByte(FASTA), 50, uniprotkb).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed[0].is_ok());
+        assert!(matches!(parsed[1], Err(UniProtHeaderError::Line(4, _))));
+    }
+
+    #[test]
+    fn no_final_newline() {
+        let parsed = parse_reader_par(&FASTA[..FASTA.len() - 1], 16, uniprotkb_iso).unwrap();
+        assert_eq!(parsed.len(), 2);
+        assert!(parsed[1].is_ok());
+    }
+}
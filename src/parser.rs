@@ -1,11 +1,13 @@
-use crate::{Database, ProteinExistence};
+use crate::{Database, HeaderLayout, MoleculeType, ProteinExistence};
 use nom::{
     branch::alt,
-    bytes::complete::{tag, take, take_until, take_while1, take_while_m_n},
-    character::{is_alphanumeric, is_digit},
-    combinator::opt,
+    bytes::complete::{
+        tag, take, take_till, take_till1, take_until, take_while, take_while1, take_while_m_n,
+    },
+    character::{is_alphabetic, is_alphanumeric, is_digit},
+    combinator::{map_res, opt, recognize, rest},
     error::ErrorKind,
-    sequence::{pair, preceded, separated_pair, tuple},
+    sequence::{pair, preceded, separated_pair, terminated, tuple},
     IResult, Slice,
 };
 use once_cell::sync::OnceCell;
@@ -42,19 +44,32 @@ pub fn db(input: &[u8]) -> IResult<&[u8], Database> {
     }
 }
 
+// UniProt accession number
+// https://www.uniprot.org/help/accession%5Fnumbers
+fn accession_regex() -> &'static Regex {
+    static RE: OnceCell<Regex> = OnceCell::new();
+    RE.get_or_init(|| {
+        Regex::new("^([OPQ][0-9][A-Z0-9]{3}[0-9]|[A-NR-Z][0-9]([A-Z][A-Z0-9]{2}[0-9]){1,2})")
+            .unwrap()
+    })
+}
+
+// Whole word is a UniProt accession number
+pub fn is_accession(word: &[u8]) -> bool {
+    accession_regex()
+        .find(word)
+        .is_some_and(|m| m.end() == word.len())
+}
+
 // UniProt unique ID
 // https://www.uniprot.org/help/accession%5Fnumbers
 pub fn unique_id(input: &[u8]) -> IResult<&[u8], &[u8]> {
-    static RE: OnceCell<Regex> = OnceCell::new();
-    let re = RE.get_or_init(|| {
-        Regex::new("[OPQ][0-9][A-Z0-9]{3}[0-9]|[A-NR-Z][0-9]([A-Z][A-Z0-9]{2}[0-9]){1,2}").unwrap()
-    });
+    let re = accession_regex();
 
-    if let Some(c) = re.captures(&input) {
+    if let Some(c) = re.captures(input) {
         let v: &[u8] = c
             .iter()
-            .filter(|el| el.is_some())
-            .map(|el| el.unwrap())
+            .flatten()
             .map(|m| input.slice(m.start()..m.end()))
             .collect::<Vec<&[u8]>>()
             .first()
@@ -71,6 +86,39 @@ pub fn unique_id(input: &[u8]) -> IResult<&[u8], &[u8]> {
         res
     }
 }
+// Start of the group ending the text, `(...)` or `[...]`
+pub fn trailing_group(text: &str) -> Option<usize> {
+    let (open, close) = match text.chars().last() {
+        Some(')') => ('(', ')'),
+        Some(']') => ('[', ']'),
+        _ => return None,
+    };
+    let mut depth = 0;
+    for (i, c) in text.char_indices().rev() {
+        if c == close {
+            depth += 1;
+        } else if c == open {
+            depth -= 1;
+            if depth == 0 {
+                return Some(i);
+            }
+        }
+    }
+    None
+}
+
+// Organism without its trailing common name and synonym, the groups starting with an uppercase letter
+pub fn scientific_name(organism: &str) -> &str {
+    let mut name = organism.trim();
+    while let Some(start) = trailing_group(name) {
+        if !name.ends_with(')') || !name[start + 1..].starts_with(|c: char| c.is_uppercase()) {
+            break;
+        }
+        name = name[..start].trim_end();
+    }
+    name
+}
+
 // UniProt entry name
 // https://www.uniprot.org/help/entry%5Fname
 pub fn entry_name(input: &[u8]) -> IResult<&[u8], Vec<u8>> {
@@ -97,6 +145,31 @@ pub fn os_until_ox(input: &[u8]) -> IResult<&[u8], &[u8]> {
     preceded(tag("OS="), take_until(" OX="))(input)
 }
 
+// Organism name of legacy headers (no OX=)
+// Until GN=, PE= or the end of the header
+pub fn os_legacy(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    preceded(
+        tag("OS="),
+        alt((take_until(" GN="), take_until(" PE="), rest)),
+    )(input)
+}
+
+// Organism name and NCBI taxonomy ID, absent from legacy headers
+pub fn organism(layout: HeaderLayout) -> impl Fn(&[u8]) -> IResult<&[u8], (&[u8], Option<&[u8]>)> {
+    move |input: &[u8]| match layout {
+        HeaderLayout::Legacy => {
+            let (input, organism) = os_legacy(input)?;
+            Ok((input, (organism, None)))
+        }
+        _ => {
+            let (input, organism) = os_until_ox(input)?;
+            let (input, _) = space(input)?;
+            let (input, organism_id) = organism_id(input)?;
+            Ok((input, (organism, Some(organism_id))))
+        }
+    }
+}
+
 // The word Fragment between parenthesis
 // Useless for now
 // pub fn fragment(input: &[u8]) -> IResult<&[u8], &[u8]> {
@@ -156,6 +229,268 @@ pub fn iso_id(input: &[u8]) -> IResult<&[u8], (&[u8], &[u8])> {
     separated_pair(unique_id, tag("-"), take_while1(is_digit))(input)
 }
 
+// Identifier and optional version
+// NCBI accessions (NP_000005.3, CAA45301.1, AAB59406) and Ensembl stable identifiers (ENSP00000269305.4)
+pub fn versioned_id(input: &[u8]) -> IResult<&[u8], (&[u8], Option<&[u8]>)> {
+    pair(
+        take_while1(|c| is_alphanumeric(c) || c == b'_'),
+        opt(preceded(tag("."), take_while1(is_digit))),
+    )(input)
+}
+
+// NCBI protein accession, RefSeq (NP_000005) or GenBank (AAB59406, MBA1234567)
+fn is_ncbi_accession(accession: &[u8]) -> bool {
+    static RE: OnceCell<Regex> = OnceCell::new();
+    RE.get_or_init(|| Regex::new("^([A-Z]{2}_[A-Z]*[0-9]+|[A-Z]{3}[0-9]{5}([0-9]{2})?)$").unwrap())
+        .is_match(accession)
+}
+
+// Legacy GenInfo prefix, identifier and database tag
+// gi|4557225|ref|
+pub fn gi_prefix(input: &[u8]) -> IResult<&[u8], (&[u8], &[u8])> {
+    let (input, _) = tag("gi|")(input)?;
+    let (input, gi) = take_while1(is_digit)(input)?;
+    let (input, _) = pipe(input)?;
+    let (input, database) = take_while1(is_alphabetic)(input)?;
+    let (input, _) = pipe(input)?;
+    Ok((input, (gi, database)))
+}
+
+// NCBI accession and optional version, after an optional GenInfo prefix closed by a pipe
+// NP_000005.3, gi|4557225|ref|NP_000005.2|
+pub fn ncbi_id(input: &[u8]) -> IResult<&[u8], (Option<(&[u8], &[u8])>, &[u8], Option<&[u8]>)> {
+    let (rest, gi) = opt(gi_prefix)(input)?;
+    let (rest, (accession, version)) = versioned_id(rest)?;
+    if !is_ncbi_accession(accession) {
+        return Err(nom::Err::Error(nom::error::make_error(
+            input,
+            ErrorKind::Verify,
+        )));
+    }
+    let (rest, _) = if gi.is_some() {
+        pipe(rest)?
+    } else {
+        (rest, &b""[..])
+    };
+    Ok((rest, (gi, accession, version)))
+}
+
+// NCBI definition until the next merged definition (\x01) or the end
+// Description and organism name, between the last brackets
+pub fn ncbi_definition(input: &[u8]) -> IResult<&[u8], (&[u8], Option<&[u8]>)> {
+    let (input, definition) = take_till(|c| c == b'\x01')(input)?;
+    Ok((input, split_organism(definition)))
+}
+
+// Split the bracketed organism name at the end of a definition, brackets can be nested
+fn split_organism(definition: &[u8]) -> (&[u8], Option<&[u8]>) {
+    let end = definition
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(0, |i| i + 1);
+    let trimmed = &definition[..end];
+    if !trimmed.ends_with(b"]") {
+        return (definition, None);
+    }
+
+    let mut depth = 0;
+    for (i, c) in trimmed.iter().enumerate().rev() {
+        match c {
+            b']' => depth += 1,
+            b'[' => {
+                depth -= 1;
+                if depth == 0 {
+                    return (&trimmed[..i], Some(&trimmed[i + 1..end - 1]));
+                }
+            }
+            _ => {}
+        }
+    }
+    (definition, None)
+}
+
+// Ensembl genomic location
+// coord_system:assembly:seq_region:start:end:strand, chromosome:GRCh38:17:7661779:7687538:-1
+pub fn genomic_location(input: &[u8]) -> IResult<&[u8], (&[u8], &[u8], &[u8], u64, u64, i8)> {
+    let field = || terminated(take_till1(|c| c == b':' || c == b' '), tag(":"));
+    let position = || {
+        terminated(
+            map_res(take_while1(is_digit), |n: &[u8]| {
+                String::from_utf8_lossy(n).parse::<u64>()
+            }),
+            tag(":"),
+        )
+    };
+    let strand = map_res(
+        recognize(pair(opt(tag("-")), take_while1(is_digit))),
+        |n: &[u8]| String::from_utf8_lossy(n).parse::<i8>(),
+    );
+
+    tuple((field(), field(), field(), position(), position(), strand))(input)
+}
+
+// Ensembl key:value
+// The description is always last and takes the rest of the line
+pub fn ensembl_key_value(input: &[u8]) -> IResult<&[u8], (&[u8], &[u8])> {
+    let (input, key) =
+        terminated(take_while1(|c| is_alphanumeric(c) || c == b'_'), tag(":"))(input)?;
+    let (input, value) = if key == b"description" {
+        rest(input)?
+    } else {
+        take_till(|c| c == b' ')(input)?
+    };
+    Ok((input, (key, value)))
+}
+
+// Split the [Source:Name;Acc:Accession] block at the end of an Ensembl description
+pub fn ensembl_source(description: &[u8]) -> (&[u8], Option<(&[u8], &[u8])>) {
+    let end = description
+        .iter()
+        .rposition(|c| !c.is_ascii_whitespace())
+        .map_or(0, |i| i + 1);
+    let trimmed = &description[..end];
+
+    let block = match memchr::memmem::rfind(trimmed, b"[Source:") {
+        Some(start) if trimmed.ends_with(b"]") => start,
+        _ => return (trimmed, None),
+    };
+    let inner = &trimmed[block + 8..end - 1];
+    match memchr::memmem::find(inner, b";Acc:") {
+        Some(acc) => (&trimmed[..block], Some((&inner[..acc], &inner[acc + 5..]))),
+        None => (trimmed, None),
+    }
+}
+
+// PDB identifier and chain
+// 101m_A, 6zj3_LA
+pub fn pdb_chain(input: &[u8]) -> IResult<&[u8], (&[u8], &[u8])> {
+    separated_pair(
+        take_while_m_n(4, 4, is_alphanumeric),
+        tag("_"),
+        take_while1(is_alphanumeric),
+    )(input)
+}
+
+// PDB molecule type
+// mol:protein or mol:na
+pub fn molecule(input: &[u8]) -> IResult<&[u8], MoleculeType> {
+    let out: IResult<&[u8], &[u8]> = preceded(tag("mol:"), alt((tag("protein"), tag("na"))))(input);
+
+    match out {
+        Ok((rest, molecule)) => match molecule {
+            b"protein" => Ok((rest, MoleculeType::Protein)),
+            b"na" => Ok((rest, MoleculeType::NucleicAcid)),
+            _ => unreachable!(),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+// PDB sequence length
+pub fn length(input: &[u8]) -> IResult<&[u8], usize> {
+    preceded(
+        tag("length:"),
+        map_res(take_while1(is_digit), |n: &[u8]| {
+            String::from_utf8_lossy(n).parse::<usize>()
+        }),
+    )(input)
+}
+
+// UniRef cluster identifier
+// UniRef90_Q9H553: identity and representative member
+pub fn uniref_id(input: &[u8]) -> IResult<&[u8], (u8, &[u8])> {
+    preceded(
+        tag("UniRef"),
+        separated_pair(
+            map_res(alt((tag("100"), tag("90"), tag("50"))), |n: &[u8]| {
+                String::from_utf8_lossy(n).parse::<u8>()
+            }),
+            tag("_"),
+            take_till1(|c| c == b' '),
+        ),
+    )(input)
+}
+
+// UniRef cluster name
+// Anything until the member count
+pub fn until_members(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    take_until(" n=")(input)
+}
+
+// UniRef member count
+pub fn members(input: &[u8]) -> IResult<&[u8], usize> {
+    preceded(
+        tag("n="),
+        map_res(take_while1(is_digit), |n: &[u8]| {
+            String::from_utf8_lossy(n).parse::<usize>()
+        }),
+    )(input)
+}
+
+// UniRef common taxon and its identifier
+// Tax=Homo sapiens TaxID=9606, TaxID is missing in older releases
+pub fn uniref_taxon(input: &[u8]) -> IResult<&[u8], (&[u8], Option<&[u8]>)> {
+    pair(
+        preceded(
+            tag("Tax="),
+            alt((take_until(" TaxID="), take_until(" RepID="))),
+        ),
+        opt(preceded(tag(" TaxID="), take_while1(is_digit))),
+    )(input)
+}
+
+// UniRef representative member entry name
+pub fn rep_id(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    preceded(tag("RepID="), take_till1(|c| c == b' '))(input)
+}
+
+// UniParc identifier
+// UPI followed by 10 hexadecimal digits
+pub fn uniparc_id(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    recognize(pair(
+        tag("UPI"),
+        take_while_m_n(10, 10, |c: u8| c.is_ascii_hexdigit()),
+    ))(input)
+}
+
+// UniParc status
+// status=active or status=inactive
+pub fn uniparc_status(input: &[u8]) -> IResult<&[u8], bool> {
+    let out: IResult<&[u8], &[u8]> =
+        preceded(tag("status="), alt((tag("active"), tag("inactive"))))(input);
+
+    match out {
+        Ok((rest, status)) => Ok((rest, status == b"active")),
+        Err(e) => Err(e),
+    }
+}
+
+// Key of a KEY=value pair, without the =
+// A letter then letters, digits or underscores
+pub fn key(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    terminated(
+        recognize(pair(
+            take_while_m_n(1, 1, is_alphabetic),
+            take_while(|c| is_alphanumeric(c) || c == b'_'),
+        )),
+        tag("="),
+    )(input)
+}
+
+// Anything until the next ` KEY=` or the end
+// Same as until_os and os_until_ox, for any key
+pub fn until_key(input: &[u8]) -> IResult<&[u8], &[u8]> {
+    let end = memchr::memchr_iter(b' ', input)
+        .find(|&i| key(&input[i + 1..]).is_ok())
+        .unwrap_or(input.len());
+    Ok((&input[end..], &input[..end]))
+}
+
+// KEY=value pair, values can contain spaces
+pub fn key_value(input: &[u8]) -> IResult<&[u8], (&[u8], &[u8])> {
+    pair(key, until_key)(input)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,6 +562,27 @@ mod tests {
         );
     }
 
+    // Accessions must start the identifier, a prefix used to be skipped and `xxP02668` parsed as `P02668`
+    #[test]
+    fn prefixed_uniprot_id() {
+        assert_eq!(
+            unique_id(b"xxP02668|CASK_BOVIN").unwrap_err(),
+            nom::Err::Error((
+                &b"xxP02668|CASK_BOVIN"[..],
+                nom::error::ErrorKind::RegexpCapture
+            ))
+        );
+        assert!(crate::uniprotkb(
+            b">sp|xxP02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1"
+        )
+        .is_err());
+
+        assert_eq!(
+            unique_id(b"P02668|CASK_BOVIN").unwrap(),
+            (&b"|CASK_BOVIN"[..], &b"P02668"[..])
+        );
+    }
+
     /* ------------------------------- entry_name ------------------------------- */
 
     #[test_case(b"ACN2_ACAGO", b"ACN2_ACAGO"; "ACN2_ACAGO")]
@@ -311,6 +667,25 @@ mod tests {
         pretty_assertions::assert_eq!(parsed, expected);
     }
 
+    /* -------------------------------- os_legacy ------------------------------- */
+
+    #[test_case(b"OS=Oryctolagus cuniculus GN=GOT2 PE=1 SV=2", b"Oryctolagus cuniculus"; "AATM_RABIT")]
+    #[test_case(b"OS=Mus musculus PE=1 SV=1", b"Mus musculus"; "no gene name")]
+    #[test_case(b"OS=Macaca fascicularis", b"Macaca fascicularis"; "isoform")]
+    fn nom_os_legacy(input: &[u8], expected: &[u8]) {
+        let (_, parsed) = os_legacy(input).unwrap();
+        pretty_assertions::assert_eq!(parsed, expected);
+    }
+
+    /* -------------------------------- organism -------------------------------- */
+
+    #[test_case(b"OS=Bacillus phage SPP1 OX=10724", HeaderLayout::Current, (b"Bacillus phage SPP1", Some(b"10724")); "current")]
+    #[test_case(b"OS=Oryctolagus cuniculus GN=GOT2", HeaderLayout::Legacy, (b"Oryctolagus cuniculus", None); "legacy")]
+    fn nom_organism(input: &[u8], layout: HeaderLayout, expected: (&[u8], Option<&[u8]>)) {
+        let (_, parsed) = organism(layout)(input).unwrap();
+        pretty_assertions::assert_eq!(parsed, expected);
+    }
+
     /* ------------------------------- organism_id ------------------------------ */
 
     #[test_case(b"OX=10724", b"10724"; "Bacillus phage SPP1")]
@@ -377,4 +752,172 @@ mod tests {
         let (_, parsed) = iso_id(input).unwrap();
         pretty_assertions::assert_eq!(parsed, expected);
     }
+
+    /* ------------------------------- versioned_id ------------------------------- */
+
+    #[test_case(b"NP_000005.3 alpha", (b"NP_000005", Some(b"3")); "refseq")]
+    #[test_case(b"AAB59406 hypothetical", (b"AAB59406", None); "without version")]
+    #[test_case(b"ENSP00000269305.4 pep", (b"ENSP00000269305", Some(b"4")); "ensembl")]
+    fn versioned_ids(input: &[u8], expected: (&[u8], Option<&[u8; 1]>)) {
+        let (_, (accession, version)) = versioned_id(input).unwrap();
+        pretty_assertions::assert_eq!(
+            (accession, version),
+            (expected.0, expected.1.map(|v| &v[..]))
+        );
+    }
+
+    /* --------------------------------- ncbi_id --------------------------------- */
+
+    #[test_case(b"NP_000005.3 alpha", None, b"NP_000005", Some(b"3"); "refseq")]
+    #[test_case(b"AAB59406 hypothetical", None, b"AAB59406", None; "genbank")]
+    #[test_case(b"MBA1234567.1 protein", None, b"MBA1234567", Some(b"1"); "genbank seven digits")]
+    #[test_case(b"gi|4557225|ref|NP_000005.2| alpha", Some((b"4557225", b"ref")), b"NP_000005", Some(b"2"); "geninfo")]
+    fn ncbi_ids(
+        input: &[u8],
+        gi: Option<(&[u8; 7], &[u8; 3])>,
+        accession: &[u8],
+        version: Option<&[u8; 1]>,
+    ) {
+        let (_, parsed) = ncbi_id(input).unwrap();
+        pretty_assertions::assert_eq!(
+            parsed,
+            (
+                gi.map(|(gi, database)| (&gi[..], &database[..])),
+                accession,
+                version.map(|v| &v[..])
+            )
+        );
+    }
+
+    #[test_case(b"foo" ; "word")]
+    #[test_case(b"seq1 OS=Homo sapiens OX=9606" ; "key value")]
+    #[test_case(b"ENSP00000269305.4 pep" ; "ensembl")]
+    #[test_case(b"gi|4557225|ref|NP_000005.2 alpha" ; "geninfo without closing pipe")]
+    fn not_ncbi_ids(input: &[u8]) {
+        assert!(ncbi_id(input).is_err());
+    }
+
+    /* ----------------------------- ncbi_definition ----------------------------- */
+
+    #[test_case(b"protein [Homo sapiens]", b"protein ", Some(b"Homo sapiens"); "organism")]
+    #[test_case(b"protein [Homo sapiens]\x01WP_1.1", b"protein ", Some(b"Homo sapiens"); "merged")]
+    #[test_case(b"protein [2Fe-2S] domain", b"protein [2Fe-2S] domain", None; "brackets in description")]
+    #[test_case(b"protein [Vibrio [Bacterium] sp.] ", b"protein ", Some(b"Vibrio [Bacterium] sp."); "nested brackets")]
+    fn ncbi_definitions(input: &[u8], description: &[u8], organism: Option<&[u8]>) {
+        let (_, parsed) = ncbi_definition(input).unwrap();
+        pretty_assertions::assert_eq!(parsed, (description, organism));
+    }
+
+    /* ----------------------------- genomic_location ---------------------------- */
+
+    #[test_case(b"chromosome:GRCh38:17:7661779:7687538:-1 gene", (b"chromosome", b"GRCh38", b"17", 7661779, 7687538, -1); "reverse")]
+    #[test_case(b"primary_assembly:Ensembl_v:KI270.1:1:20:1", (b"primary_assembly", b"Ensembl_v", b"KI270.1", 1, 20, 1); "forward")]
+    fn genomic_locations(input: &[u8], expected: (&[u8], &[u8], &[u8], u64, u64, i8)) {
+        let (_, parsed) = genomic_location(input).unwrap();
+        pretty_assertions::assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn incomplete_genomic_location() {
+        assert!(genomic_location(b"chromosome:GRCh38:17:7661779").is_err());
+    }
+
+    /* ----------------------------- ensembl_source ------------------------------ */
+
+    #[test_case(b"tumor protein p53 [Source:HGNC Symbol;Acc:HGNC:11998]", b"tumor protein p53 ", Some((b"HGNC Symbol", b"HGNC:11998")); "hgnc")]
+    #[test_case(b"Nep3 ", b"Nep3", None; "without source")]
+    #[test_case(b"x [Source:unknown]", b"x [Source:unknown]", None; "without accession")]
+    fn ensembl_sources(input: &[u8], description: &[u8], source: Option<(&[u8; 11], &[u8; 10])>) {
+        let parsed = ensembl_source(input);
+        pretty_assertions::assert_eq!(parsed, (description, source.map(|(n, a)| (&n[..], &a[..]))));
+    }
+
+    /* -------------------------------- pdb_chain -------------------------------- */
+
+    #[test_case(b"101m_A mol", (b"101m", b"A"); "single letter chain")]
+    #[test_case(b"6zj3_LA mol", (b"6zj3", b"LA"); "two letters chain")]
+    fn pdb_chains(input: &[u8], expected: (&[u8], &[u8])) {
+        let (_, parsed) = pdb_chain(input).unwrap();
+        pretty_assertions::assert_eq!(parsed, expected);
+    }
+
+    /* -------------------------------- molecule --------------------------------- */
+
+    #[test_case(b"mol:protein", MoleculeType::Protein ; "protein")]
+    #[test_case(b"mol:na", MoleculeType::NucleicAcid ; "nucleic acid")]
+    fn molecules(input: &[u8], expected: MoleculeType) {
+        let (_, parsed) = molecule(input).unwrap();
+        pretty_assertions::assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn unknown_molecule() {
+        assert!(molecule(b"mol:dna").is_err());
+    }
+
+    /* --------------------------------- length ---------------------------------- */
+
+    #[test]
+    fn pdb_length() {
+        assert_eq!(
+            length(b"length:154  MYOGLOBIN").unwrap(),
+            (&b"  MYOGLOBIN"[..], 154)
+        );
+    }
+
+    /* -------------------------------- uniref_id -------------------------------- */
+
+    #[test_case(b"UniRef90_Q9H553 Alpha", (90, b"Q9H553"); "90")]
+    #[test_case(b"UniRef100_UPI0000000001 x", (100, b"UPI0000000001"); "uniparc member")]
+    fn uniref_ids(input: &[u8], expected: (u8, &[u8])) {
+        let (_, parsed) = uniref_id(input).unwrap();
+        pretty_assertions::assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn invalid_uniref_identity() {
+        assert!(uniref_id(b"UniRef80_Q9H553").is_err());
+    }
+
+    /* ------------------------------ uniref_taxon ------------------------------- */
+
+    #[test_case(b"Tax=Homo sapiens TaxID=9606 RepID=ALG2_HUMAN", (b"Homo sapiens", Some(b"9606")); "current")]
+    #[test_case(b"Tax=Homo sapiens RepID=ALG2_HUMAN", (b"Homo sapiens", None); "without taxid")]
+    fn uniref_taxons(input: &[u8], expected: (&[u8], Option<&[u8; 4]>)) {
+        let (_, parsed) = uniref_taxon(input).unwrap();
+        pretty_assertions::assert_eq!(parsed, (expected.0, expected.1.map(|t| &t[..])));
+    }
+
+    /* ------------------------------- uniparc_id -------------------------------- */
+
+    #[test]
+    fn uniparc_ids() {
+        assert_eq!(
+            uniparc_id(b"UPI0000000001 status=active").unwrap(),
+            (&b" status=active"[..], &b"UPI0000000001"[..])
+        );
+        assert!(uniparc_id(b"UPI00000001").is_err());
+    }
+
+    #[test_case(b"status=active", true ; "active")]
+    #[test_case(b"status=inactive", false ; "inactive")]
+    fn uniparc_statuses(input: &[u8], expected: bool) {
+        let (_, parsed) = uniparc_status(input).unwrap();
+        pretty_assertions::assert_eq!(parsed, expected);
+    }
+
+    /* -------------------------------- key_value -------------------------------- */
+
+    #[test_case(b"OS=Homo sapiens OX=9606", (b"OS", b"Homo sapiens"), b" OX=9606"; "value with spaces")]
+    #[test_case(b"sample=S4", (b"sample", b"S4"), b""; "last")]
+    #[test_case(b"note=a = b x_1=2", (b"note", b"a = b"), b" x_1=2"; "equal sign in value")]
+    fn key_values(input: &[u8], expected: (&[u8], &[u8]), remaining: &[u8]) {
+        pretty_assertions::assert_eq!(key_value(input).unwrap(), (remaining, expected));
+    }
+
+    #[test]
+    fn invalid_key() {
+        assert!(key(b"1x=2").is_err());
+        assert!(key(b"=2").is_err());
+    }
 }
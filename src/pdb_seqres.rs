@@ -0,0 +1,175 @@
+use crate::{
+    error::UniProtHeaderError,
+    filter::{Field, Filterable},
+    header::FastaHeader,
+    parser::{chevron, length, molecule, pdb_chain, space},
+    MoleculeType,
+};
+use nom::{
+    combinator::{all_consuming, opt, rest},
+    error::ParseError,
+    sequence::preceded,
+    IResult,
+};
+use std::{borrow::Cow, fmt};
+
+/// PDB SEQRES header (`pdb_seqres.txt`)
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PdbSeqres {
+    /// PDB identifier (`101m`)
+    pub pdb_id: String,
+    /// Chain identifier (`A`)
+    pub chain: String,
+    /// Molecule type
+    pub molecule: MoleculeType,
+    /// Sequence length
+    pub length: usize,
+    /// Molecule name
+    pub name: String,
+}
+
+impl Default for PdbSeqres {
+    fn default() -> Self {
+        Self {
+            pdb_id: String::default(),
+            chain: String::default(),
+            molecule: MoleculeType::Protein,
+            length: 0,
+            name: String::default(),
+        }
+    }
+}
+
+/// Write the PDB SEQRES fasta header
+impl fmt::Display for PdbSeqres {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ">{}_{} mol:{} length:{}  {}",
+            self.pdb_id,
+            self.chain,
+            self.molecule.code(),
+            self.length,
+            self.name
+        )
+    }
+}
+
+impl Filterable for PdbSeqres {
+    fn field(&self, field: Field) -> Option<Cow<'_, str>> {
+        match field {
+            Field::Database => Some(Cow::Borrowed("pdb")),
+            Field::Accession => Some(Cow::Borrowed(&self.pdb_id)),
+            Field::EntryName => Some(Cow::Owned(format!("{}_{}", self.pdb_id, self.chain))),
+            Field::ProteinName => Some(Cow::Borrowed(&self.name)),
+            _ => None,
+        }
+    }
+}
+
+impl FastaHeader for PdbSeqres {
+    fn identifier(&self) -> Cow<'_, str> {
+        Cow::Owned(format!("{}_{}", self.pdb_id, self.chain))
+    }
+
+    fn organism_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn taxon_id(&self) -> Option<&str> {
+        None
+    }
+
+    fn gene_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn description(&self) -> &str {
+        &self.name
+    }
+
+    fn source_database(&self) -> &'static str {
+        "pdb"
+    }
+}
+
+/// Parse a PDB SEQRES fasta header
+pub fn pdb_seqres(string: &[u8]) -> Result<PdbSeqres, UniProtHeaderError> {
+    match parse_pdb_seqres(string) {
+        Ok((_, parsed)) => Ok(parsed),
+        Err(err) => match err {
+            nom::Err::Incomplete(_i) => Err(UniProtHeaderError::Incomplete),
+            nom::Err::Error((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+            nom::Err::Failure((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+        },
+    }
+}
+
+fn parse_pdb_seqres(input: &[u8]) -> IResult<&[u8], PdbSeqres> {
+    let (input, _) = chevron(input)?;
+    let (input, (pdb_id, chain)) = pdb_chain(input)?;
+    let (input, molecule) = preceded(space, molecule)(input)?;
+    let (input, length) = preceded(space, length)(input)?;
+    let (input, name) = all_consuming(opt(preceded(space, rest)))(input)?;
+
+    Ok((
+        input,
+        PdbSeqres {
+            pdb_id: String::from_utf8_lossy(pdb_id).to_string(),
+            chain: String::from_utf8_lossy(chain).to_string(),
+            molecule,
+            length,
+            name: name
+                .map(|n| String::from_utf8_lossy(n).trim().to_string())
+                .unwrap_or_default(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_pdb_seqres_101m_a() {
+        let entry = PdbSeqres {
+            pdb_id: "101m".to_string(),
+            chain: "A".to_string(),
+            molecule: MoleculeType::Protein,
+            length: 154,
+            name: "MYOGLOBIN".to_string(),
+        };
+        let test_header = ">101m_A mol:protein length:154  MYOGLOBIN";
+        let parsed = pdb_seqres(test_header.as_bytes()).unwrap();
+        assert_eq!(parsed, entry);
+        assert_eq!(parsed.to_string(), test_header);
+    }
+
+    #[test]
+    fn test_pdb_seqres_nucleic_acid() {
+        let parsed = pdb_seqres(
+            b">1a1t_B mol:na length:20  DNA (5'-D(*CP*GP*CP*GP*AP*AP*TP*TP*CP*GP*CP*G)-3')",
+        )
+        .unwrap();
+        assert_eq!(parsed.molecule, MoleculeType::NucleicAcid);
+        assert_eq!(
+            parsed.name,
+            "DNA (5'-D(*CP*GP*CP*GP*AP*AP*TP*TP*CP*GP*CP*G)-3')"
+        );
+    }
+
+    #[test]
+    fn test_pdb_seqres_without_name() {
+        let parsed = pdb_seqres(b">6zj3_LA mol:protein length:8").unwrap();
+        assert_eq!(parsed.chain, "LA");
+        assert_eq!(parsed.name, "");
+    }
+
+    #[test]
+    fn test_pdb_seqres_invalid() {
+        assert!(pdb_seqres(b">101m_A mol:protein length:x  MYOGLOBIN").is_err());
+        assert!(pdb_seqres(b">101m_A mol:protein length:154MYOGLOBIN").is_err());
+    }
+}
@@ -0,0 +1,502 @@
+use crate::{
+    error::UniProtHeaderError,
+    filter::{trim_newline, Field, Filterable},
+    uniprotkb, uniprotkb_iso,
+};
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+    str::FromStr,
+};
+
+#[derive(Debug, PartialEq, Clone)]
+enum Segment {
+    Literal(String),
+    // Fields tried in order, with the placeholder text for error messages
+    Placeholder(Vec<Field>, String),
+}
+
+/// Header template
+///
+/// Placeholders are written between braces with the same field names as filter expressions
+/// (`{accession}`, `{entry_name}`, `{gene}`, `{ox}`, ...). Fallbacks are separated by `|`: `{gene|entry_name}`
+/// uses the gene name when present and the entry name otherwise. Everything outside braces is copied as is.
+///
+/// ```rust
+/// use uniprot_fasta_header::Template;
+///
+/// let template: Template = "{accession}|{gene|entry_name}|{ox}".parse().unwrap();
+///
+/// let header = uniprot_fasta_header::uniprotkb(b">sp|P18355|YPFU_ECOLI Uncharacterized protein in traD-traI intergenic region OS=Escherichia coli (strain K12) OX=83333 PE=3 SV=1").unwrap();
+///
+/// assert_eq!(template.render(&header).unwrap(), "P18355|YPFU_ECOLI|83333");
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl FromStr for Template {
+    type Err = UniProtHeaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = Vec::new();
+        let mut rest = s;
+
+        while !rest.is_empty() {
+            match rest.find('{') {
+                Some(0) => {
+                    let end = rest.find('}').ok_or_else(|| {
+                        UniProtHeaderError::InvalidTemplate(
+                            rest.to_string(),
+                            "unclosed placeholder".to_string(),
+                        )
+                    })?;
+                    let placeholder = &rest[1..end];
+                    let fields = placeholder
+                        .split('|')
+                        .map(|name| {
+                            name.trim().parse::<Field>().map_err(|_| {
+                                UniProtHeaderError::InvalidTemplate(
+                                    rest.to_string(),
+                                    format!("unknown field `{}`", name),
+                                )
+                            })
+                        })
+                        .collect::<Result<Vec<Field>, UniProtHeaderError>>()?;
+                    segments.push(Segment::Placeholder(fields, placeholder.to_string()));
+                    rest = &rest[end + 1..];
+                }
+                Some(start) => {
+                    segments.push(Segment::Literal(rest[..start].to_string()));
+                    rest = &rest[start..];
+                }
+                None => {
+                    segments.push(Segment::Literal(rest.to_string()));
+                    rest = "";
+                }
+            }
+        }
+
+        if let Some(Segment::Literal(l)) = segments
+            .iter()
+            .find(|segment| matches!(segment, Segment::Literal(literal) if literal.contains('}')))
+        {
+            return Err(UniProtHeaderError::InvalidTemplate(
+                l.to_string(),
+                "unopened placeholder".to_string(),
+            ));
+        }
+
+        Ok(Template { segments })
+    }
+}
+
+impl Template {
+    /// Render the template for a parsed header
+    ///
+    /// Fails when none of the fields of a placeholder are present in the header.
+    pub fn render<H: Filterable + ?Sized>(&self, header: &H) -> Result<String, UniProtHeaderError> {
+        let mut out = String::new();
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => out.push_str(literal),
+                Segment::Placeholder(fields, placeholder) => {
+                    let value = fields
+                        .iter()
+                        .filter_map(|field| header.field(*field))
+                        .find(|value| !value.is_empty())
+                        .ok_or_else(|| {
+                            UniProtHeaderError::MissingTemplateValue(placeholder.to_string())
+                        })?;
+                    out.push_str(&value);
+                }
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+// Characters that end a name in alignment, tree and tabular outputs
+fn is_name_delimiter(c: char) -> bool {
+    c.is_whitespace() || "(),:;[]'\"=".contains(c)
+}
+
+// Delimiters followed by a name: table columns and Newick labels
+fn starts_name(c: char) -> bool {
+    "\t(),'\"".contains(c)
+}
+
+/// Correspondence between rewritten and original headers
+///
+/// Headers are stored without the leading `>`.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct HeaderMapping {
+    entries: Vec<(String, String)>,
+    index: HashMap<String, usize>,
+}
+
+impl HeaderMapping {
+    /// Empty mapping
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a rewritten header and its original
+    ///
+    /// Fails if the rewritten header is already used by another original header.
+    pub fn insert(&mut self, new: String, original: String) -> Result<(), UniProtHeaderError> {
+        if let Some(&i) = self.index.get(&new) {
+            if self.entries[i].1 == original {
+                return Ok(());
+            }
+            return Err(UniProtHeaderError::DuplicateHeader(new));
+        }
+        self.index.insert(new.clone(), self.entries.len());
+        self.entries.push((new, original));
+        Ok(())
+    }
+
+    /// Original header of a rewritten header
+    pub fn original(&self, new: &str) -> Option<&str> {
+        self.index.get(new).map(|&i| self.entries[i].1.as_str())
+    }
+
+    /// Number of headers
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// No headers
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over `(rewritten, original)` pairs in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(n, o)| (n.as_str(), o.as_str()))
+    }
+
+    /// Write the mapping as a two columns tab separated table
+    pub fn write_tsv<W: Write>(&self, mut writer: W) -> Result<(), UniProtHeaderError> {
+        for (new, original) in self.iter() {
+            writeln!(writer, "{}\t{}", new, original)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read a mapping written by [`HeaderMapping::write_tsv`]
+    pub fn read_tsv<R: BufRead>(reader: R) -> Result<Self, UniProtHeaderError> {
+        let mut mapping = HeaderMapping::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.is_empty() {
+                continue;
+            }
+            match line.split_once('\t') {
+                Some((new, original)) => mapping.insert(new.to_string(), original.to_string())?,
+                None => {
+                    return Err(UniProtHeaderError::ParsingError(
+                        line,
+                        "missing tab separator".to_string(),
+                    ))
+                }
+            }
+        }
+        Ok(mapping)
+    }
+
+    /// Put the original headers back in a FASTA file
+    ///
+    /// A header is restored when its whole text, or its first word, is a rewritten header. Other lines are copied unchanged.
+    /// Returns the number of restored headers.
+    pub fn restore_fasta<R: BufRead, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<usize, UniProtHeaderError> {
+        let mut line = Vec::new();
+        let mut counter: usize = 0;
+
+        loop {
+            line.clear();
+            if reader.read_until(b'\n', &mut line)? == 0 {
+                break;
+            }
+
+            if line.starts_with(b">") {
+                let header = String::from_utf8_lossy(&trim_newline(&line)[1..]).to_string();
+                let first_word = header.split_whitespace().next().unwrap_or_default();
+                if let Some(original) = self.original(&header).or_else(|| self.original(first_word))
+                {
+                    writeln!(writer, ">{}", original)?;
+                    counter += 1;
+                    continue;
+                }
+            }
+
+            writer.write_all(&line)?;
+        }
+
+        writer.flush()?;
+        Ok(counter)
+    }
+
+    /// Put the original headers back in any text output (PHYLIP alignments, Newick trees, tables, ...)
+    ///
+    /// Only words at name positions are replaced: the first word of a line, a word after a tab, and Newick labels
+    /// after `(`, `,`, `)` or a quote. Sequence words after a name are left as they are. Strict PHYLIP, where a
+    /// 10 characters name runs straight into the sequence, is not supported: write relaxed PHYLIP instead.
+    /// Returns the number of replacements.
+    pub fn restore_text<R: BufRead, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> Result<usize, UniProtHeaderError> {
+        let mut line = String::new();
+        let mut counter: usize = 0;
+
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+
+            let mut rest = line.as_str();
+            let mut name_position = true;
+            while !rest.is_empty() {
+                let end = rest.find(is_name_delimiter).unwrap_or(rest.len());
+                let (word, tail) = rest.split_at(end);
+                match self.original(word) {
+                    Some(original) if name_position && !word.is_empty() => {
+                        writer.write_all(original.as_bytes())?;
+                        counter += 1;
+                    }
+                    _ => writer.write_all(word.as_bytes())?,
+                }
+                if !word.is_empty() {
+                    name_position = false;
+                }
+                match tail.chars().next() {
+                    Some(delimiter) => {
+                        if starts_name(delimiter) {
+                            name_position = true;
+                        } else if !delimiter.is_whitespace() {
+                            name_position = false;
+                        }
+                        writer.write_all(&tail.as_bytes()[..delimiter.len_utf8()])?;
+                        rest = &tail[delimiter.len_utf8()..];
+                    }
+                    None => rest = tail,
+                }
+            }
+        }
+
+        writer.flush()?;
+        Ok(counter)
+    }
+}
+
+/// Rewrite parsed headers with a template and keep track of the originals
+#[derive(Debug, Clone)]
+pub struct Rewriter {
+    template: Template,
+    mapping: HeaderMapping,
+}
+
+impl Rewriter {
+    /// New rewriter with an empty mapping
+    pub fn new(template: Template) -> Self {
+        Self {
+            template,
+            mapping: HeaderMapping::new(),
+        }
+    }
+
+    /// Rewritten header line, with the leading `>`
+    ///
+    /// `line` is the header line `header` was parsed from, it is recorded verbatim without `>` and the line ending.
+    pub fn rewrite<H: Filterable + ?Sized>(
+        &mut self,
+        header: &H,
+        line: &[u8],
+    ) -> Result<String, UniProtHeaderError> {
+        let new = self.template.render(header)?;
+        let line = trim_newline(line);
+        let original = line.strip_prefix(b">").unwrap_or(line);
+        self.mapping
+            .insert(new.clone(), String::from_utf8_lossy(original).to_string())?;
+        Ok(format!(">{}", new))
+    }
+
+    /// Mapping between the rewritten and the original headers
+    pub fn mapping(&self) -> &HeaderMapping {
+        &self.mapping
+    }
+
+    /// Consume the rewriter and return its mapping
+    pub fn into_mapping(self) -> HeaderMapping {
+        self.mapping
+    }
+}
+
+/// Rewrite the UniProtKB and UniProtKB isoform headers of a FASTA file
+///
+/// Original header lines are recorded verbatim in the returned mapping. Sequences and headers that cannot be parsed are copied unchanged.
+pub fn rewrite_fasta<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    template: &Template,
+) -> Result<HeaderMapping, UniProtHeaderError> {
+    let mut rewriter = Rewriter::new(template.clone());
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+
+        if line.starts_with(b">") {
+            let header = trim_newline(&line);
+            let new = match uniprotkb(header) {
+                Ok(parsed) => Some(rewriter.rewrite(&parsed, header)?),
+                Err(_) => match uniprotkb_iso(header) {
+                    Ok(parsed) => Some(rewriter.rewrite(&parsed, header)?),
+                    Err(_) => None,
+                },
+            };
+            if let Some(new) = new {
+                writeln!(writer, "{}", new)?;
+                continue;
+            }
+        }
+
+        writer.write_all(&line)?;
+    }
+
+    writer.flush()?;
+    Ok(rewriter.into_mapping())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    const FASTA: &[u8] = b">sp|Q9H553|ALG2_HUMAN Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1\nMAEE\n>sp|P18355|YPFU_ECOLI Uncharacterized protein in traD-traI intergenic region OS=Escherichia coli (strain K12) OX=83333 PE=3 SV=1\nMKQS\n";
+
+    #[test_case("{accession}|{gene}|{ox}", "Q9H553|ALG2|9606" ; "pipes")]
+    #[test_case("{ac}_{pe}", "Q9H553_1" ; "aliases")]
+    #[test_case("{gene|entry_name}", "ALG2" ; "fallback")]
+    #[test_case("plain", "plain" ; "literal")]
+    fn render(template: &str, expected: &str) {
+        let header = uniprotkb(b">sp|Q9H553|ALG2_HUMAN Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1").unwrap();
+        let template: Template = template.parse().unwrap();
+        pretty_assertions::assert_eq!(template.render(&header).unwrap(), expected);
+    }
+
+    #[test]
+    fn render_missing() {
+        let header = uniprotkb(b">sp|P18355|YPFU_ECOLI Uncharacterized protein in traD-traI intergenic region OS=Escherichia coli (strain K12) OX=83333 PE=3 SV=1").unwrap();
+        let template: Template = "{gene}".parse().unwrap();
+        assert_eq!(
+            template.render(&header).unwrap_err(),
+            UniProtHeaderError::MissingTemplateValue("gene".to_string())
+        );
+    }
+
+    #[test_case("{accession" ; "unclosed")]
+    #[test_case("accession}" ; "unopened")]
+    #[test_case("{foo}" ; "unknown field")]
+    fn invalid(template: &str) {
+        assert!(template.parse::<Template>().is_err());
+    }
+
+    #[test]
+    fn rewriter() {
+        let line = b">sp|Q9H553|ALG2_HUMAN  Alpha-1,3/1,6-mannosyltransferase ALG2  OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1\r\n";
+        let header = uniprotkb(trim_newline(line)).unwrap();
+        let mut rewriter = Rewriter::new("{gene}".parse().unwrap());
+        assert_eq!(rewriter.rewrite(&header, line).unwrap(), ">ALG2");
+        assert_eq!(
+            rewriter.mapping().original("ALG2"),
+            Some("sp|Q9H553|ALG2_HUMAN  Alpha-1,3/1,6-mannosyltransferase ALG2  OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1")
+        );
+    }
+
+    #[test]
+    fn restore_text_names_only() {
+        let mut mapping = HeaderMapping::new();
+        mapping
+            .insert("AB".to_string(), "sp|P1|A".to_string())
+            .unwrap();
+        mapping
+            .insert("CD".to_string(), "sp|P2|C".to_string())
+            .unwrap();
+
+        let text: &[u8] = b"2 4\nAB    AB CD\n  CD  ABCD\n((AB:0.1,'CD'),x)AB;\nrow\tAB\tCD\n";
+        let mut restored = Vec::new();
+        assert_eq!(mapping.restore_text(text, &mut restored).unwrap(), 7);
+        assert_eq!(
+            String::from_utf8(restored).unwrap(),
+            "2 4\nsp|P1|A    AB CD\n  sp|P2|C  ABCD\n((sp|P1|A:0.1,'sp|P2|C'),x)sp|P1|A;\nrow\tsp|P1|A\tsp|P2|C\n"
+        );
+    }
+
+    #[test]
+    fn restore_text_strict_phylip() {
+        let mut mapping = HeaderMapping::new();
+        mapping
+            .insert("ALG2_HUMAN".to_string(), "sp|Q9H553|ALG2_HUMAN".to_string())
+            .unwrap();
+        let mut restored = Vec::new();
+        assert_eq!(
+            mapping
+                .restore_text(&b"1 4\nALG2_HUMANMAEE\n"[..], &mut restored)
+                .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn duplicate() {
+        let mut mapping = HeaderMapping::new();
+        mapping.insert("a".to_string(), "x".to_string()).unwrap();
+        mapping.insert("a".to_string(), "x".to_string()).unwrap();
+        assert_eq!(
+            mapping
+                .insert("a".to_string(), "y".to_string())
+                .unwrap_err(),
+            UniProtHeaderError::DuplicateHeader("a".to_string())
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let template: Template = "{accession}|{gene|entry_name}".parse().unwrap();
+        let mut rewritten = Vec::new();
+        let mapping = rewrite_fasta(FASTA, &mut rewritten, &template).unwrap();
+        assert_eq!(
+            String::from_utf8(rewritten.clone()).unwrap(),
+            ">Q9H553|ALG2\nMAEE\n>P18355|YPFU_ECOLI\nMKQS\n"
+        );
+
+        let mut tsv = Vec::new();
+        mapping.write_tsv(&mut tsv).unwrap();
+        let mapping = HeaderMapping::read_tsv(&tsv[..]).unwrap();
+
+        let mut restored = Vec::new();
+        assert_eq!(
+            mapping
+                .restore_fasta(&rewritten[..], &mut restored)
+                .unwrap(),
+            2
+        );
+        assert_eq!(restored, FASTA);
+    }
+}
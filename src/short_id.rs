@@ -0,0 +1,253 @@
+use crate::{
+    error::UniProtHeaderError,
+    filter::{trim_newline, Field, Filterable},
+    rewrite::HeaderMapping,
+    uniprotkb, uniprotkb_iso,
+};
+use std::{
+    fmt,
+    io::{BufRead, Write},
+};
+
+/// Maximum name length of the PHYLIP format
+pub const PHYLIP_NAME_LENGTH: usize = 10;
+
+/// Header field the short identifiers are derived from
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ShortIdSource {
+    /// Accession number, with the isoform number for isoforms (`Q4R572-2`)
+    Accession,
+    /// Entry name (`1433B_MACFA`)
+    EntryName,
+}
+
+/// Assign collision free short identifiers to headers
+///
+/// Identifiers are truncated to `max_length` characters. When an identifier is already taken, the end is replaced by a counter.
+/// Every call gets a new identifier, so repeated headers still get distinct names.
+///
+/// ```rust
+/// use uniprot_fasta_header::{ShortIdGenerator, ShortIdSource, PHYLIP_NAME_LENGTH};
+///
+/// let mut generator = ShortIdGenerator::new(ShortIdSource::EntryName, PHYLIP_NAME_LENGTH).unwrap();
+///
+/// let header = uniprot_fasta_header::uniprotkb(b">tr|Q3SA23|Q3SA23_9HIV1 Protein Nef (Fragment) OS=Human immunodeficiency virus 1 OX=11676 GN=nef PE=3 SV=1").unwrap();
+///
+/// assert_eq!(generator.assign(&header).unwrap(), "Q3SA23_9HI");
+/// assert_eq!(generator.assign(&header).unwrap(), "Q3SA23_9H1");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ShortIdGenerator {
+    source: ShortIdSource,
+    max_length: usize,
+    mapping: HeaderMapping,
+}
+
+// Characters that most legacy formats (PHYLIP, Newick) accept in names
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl ShortIdGenerator {
+    /// New generator, `max_length` must be at least 2
+    pub fn new(source: ShortIdSource, max_length: usize) -> Result<Self, UniProtHeaderError> {
+        if max_length < 2 {
+            return Err(UniProtHeaderError::InvalidShortIdLength(max_length));
+        }
+        Ok(Self {
+            source,
+            max_length,
+            mapping: HeaderMapping::new(),
+        })
+    }
+
+    /// Short identifier of a parsed header, recorded in the mapping with the header text
+    pub fn assign<H: Filterable + fmt::Display + ?Sized>(
+        &mut self,
+        header: &H,
+    ) -> Result<String, UniProtHeaderError> {
+        let original = header.to_string();
+        let original = original.strip_prefix('>').unwrap_or(&original).to_string();
+        let base = self.base(header);
+        self.assign_base(&base, original)
+    }
+
+    fn base<H: Filterable + ?Sized>(&self, header: &H) -> String {
+        match self.source {
+            ShortIdSource::Accession => {
+                let accession = header.field(Field::Accession).unwrap_or_default();
+                match header.field(Field::Isoform) {
+                    Some(isoform) => format!("{}-{}", accession, isoform),
+                    None => accession.to_string(),
+                }
+            }
+            ShortIdSource::EntryName => header
+                .field(Field::EntryName)
+                .unwrap_or_default()
+                .to_string(),
+        }
+    }
+
+    fn assign_base(&mut self, base: &str, original: String) -> Result<String, UniProtHeaderError> {
+        let base = sanitize(base);
+        let mut candidate: String = base.chars().take(self.max_length).collect();
+        let mut counter: usize = 0;
+
+        while candidate.is_empty() || self.mapping.original(&candidate).is_some() {
+            counter += 1;
+            let suffix = counter.to_string();
+            if suffix.len() >= self.max_length {
+                return Err(UniProtHeaderError::ShortIdExhausted(base, self.max_length));
+            }
+            candidate = base
+                .chars()
+                .take(self.max_length - suffix.len())
+                .chain(suffix.chars())
+                .collect();
+        }
+
+        self.mapping.insert(candidate.clone(), original)?;
+        Ok(candidate)
+    }
+
+    /// Mapping between the short identifiers and the original headers
+    pub fn mapping(&self) -> &HeaderMapping {
+        &self.mapping
+    }
+
+    /// Consume the generator and return its mapping
+    pub fn into_mapping(self) -> HeaderMapping {
+        self.mapping
+    }
+}
+
+/// Replace every header of a FASTA file by a short identifier
+///
+/// Headers that are neither UniProtKB nor UniProtKB isoform headers are shortened from their first word.
+/// Original header lines are recorded verbatim in the returned mapping, sequences are copied unchanged.
+pub fn shorten_fasta<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    source: ShortIdSource,
+    max_length: usize,
+) -> Result<HeaderMapping, UniProtHeaderError> {
+    let mut generator = ShortIdGenerator::new(source, max_length)?;
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+
+        if line.starts_with(b">") {
+            let header = trim_newline(&line);
+            let base = match uniprotkb(header) {
+                Ok(parsed) => generator.base(&parsed),
+                Err(_) => match uniprotkb_iso(header) {
+                    Ok(parsed) => generator.base(&parsed),
+                    Err(_) => String::from_utf8_lossy(&header[1..])
+                        .split_whitespace()
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                },
+            };
+            let original = String::from_utf8_lossy(&header[1..]).to_string();
+            let short = generator.assign_base(&base, original)?;
+            writeln!(writer, ">{}", short)?;
+        } else {
+            writer.write_all(&line)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(generator.into_mapping())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn accession() {
+        let mut generator = ShortIdGenerator::new(ShortIdSource::Accession, 10).unwrap();
+        let header = uniprotkb_iso(b">sp|Q4R572-2|1433B_MACFA Isoform Short of 14-3-3 protein beta/alpha OS=Macaca fascicularis OX=9541 GN=YWHAB").unwrap();
+        assert_eq!(generator.assign(&header).unwrap(), "Q4R572-2");
+        let header = uniprotkb(b">tr|A0A023GPI8|LECA_CANBL Lectin alpha chain OS=Canavalia boliviana OX=232300 PE=1 SV=1").unwrap();
+        assert_eq!(generator.assign(&header).unwrap(), "A0A023GPI8");
+    }
+
+    #[test]
+    fn collisions() {
+        let mut generator = ShortIdGenerator::new(ShortIdSource::EntryName, 4).unwrap();
+        let ids: Vec<String> = (0..11)
+            .map(|i| generator.assign_base("ABCDEF", i.to_string()).unwrap())
+            .collect();
+        assert_eq!(ids[0], "ABCD");
+        assert_eq!(ids[1], "ABC1");
+        assert_eq!(ids[10], "AB10");
+        assert_eq!(generator.mapping().len(), 11);
+    }
+
+    #[test]
+    fn repeated_headers() {
+        let fasta: &[u8] = b">seq_a long name\nMK\n>seq_a long name\nQS\n";
+        let mut shortened = Vec::new();
+        let mapping = shorten_fasta(fasta, &mut shortened, ShortIdSource::EntryName, 10).unwrap();
+        assert_eq!(
+            String::from_utf8(shortened).unwrap(),
+            ">seq_a\nMK\n>seq_a1\nQS\n"
+        );
+        assert_eq!(mapping.len(), 2);
+        assert_eq!(mapping.original("seq_a1"), Some("seq_a long name"));
+    }
+
+    #[test]
+    fn exhausted() {
+        let mut generator = ShortIdGenerator::new(ShortIdSource::EntryName, 2).unwrap();
+        for i in 0..10 {
+            generator.assign_base("AB", i.to_string()).unwrap();
+        }
+        assert_eq!(
+            generator.assign_base("AB", "10".to_string()).unwrap_err(),
+            UniProtHeaderError::ShortIdExhausted("AB".to_string(), 2)
+        );
+    }
+
+    #[test]
+    fn too_short() {
+        assert_eq!(
+            ShortIdGenerator::new(ShortIdSource::EntryName, 1).unwrap_err(),
+            UniProtHeaderError::InvalidShortIdLength(1)
+        );
+    }
+
+    #[test]
+    fn fasta_round_trip() {
+        let fasta: &[u8] = b">sp|Q9H553|ALG2_HUMAN Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1\nMAEE\n>custom header\nMKQS\n";
+        let mut shortened = Vec::new();
+        let mapping = shorten_fasta(fasta, &mut shortened, ShortIdSource::EntryName, 10).unwrap();
+        assert_eq!(
+            String::from_utf8(shortened).unwrap(),
+            ">ALG2_HUMAN\nMAEE\n>custom\nMKQS\n"
+        );
+
+        let phylip: &[u8] = b"2 4\nALG2_HUMAN MAEE\ncustom     MKQS\n";
+        let mut restored = Vec::new();
+        assert_eq!(mapping.restore_text(phylip, &mut restored).unwrap(), 2);
+        assert_eq!(
+            String::from_utf8(restored).unwrap(),
+            "2 4\nsp|Q9H553|ALG2_HUMAN Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2 PE=1 SV=1 MAEE\ncustom header     MKQS\n"
+        );
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{error::UniProtHeaderError, filter::trim_newline};
+use once_cell::sync::OnceCell;
+use regex::Regex;
+use std::{collections::HashMap, fmt, io::BufRead, str::FromStr};
+
+/// UniProt entry name, `ProteinMnemonic_SpeciesCode`
+///
+/// ```rust
+/// let name: uniprot_fasta_header::EntryName = "CASK_BOVIN".parse().unwrap();
+///
+/// assert_eq!(name.mnemonic, "CASK");
+/// assert_eq!(name.species, "BOVIN");
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EntryName {
+    /// Protein mnemonic (Swiss-Prot) or accession (TrEMBL)
+    pub mnemonic: String,
+    /// Species code, `9` followed by 4 characters for virtual codes
+    pub species: String,
+}
+
+impl EntryName {
+    /// Virtual species codes regroup the organisms of a higher taxon (`9HIV1`)
+    pub fn is_virtual(&self) -> bool {
+        self.species.starts_with('9')
+    }
+}
+
+impl FromStr for EntryName {
+    type Err = UniProtHeaderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let valid =
+            |part: &str| !part.is_empty() && part.bytes().all(|c| c.is_ascii_alphanumeric());
+        match s.rsplit_once('_') {
+            Some((mnemonic, species))
+                if valid(mnemonic) && valid(species) && species.len() <= 5 =>
+            {
+                Ok(EntryName {
+                    mnemonic: mnemonic.to_string(),
+                    species: species.to_string(),
+                })
+            }
+            _ => Err(UniProtHeaderError::ParsingError(
+                s.to_string(),
+                "invalid entry name".to_string(),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for EntryName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}_{}", self.mnemonic, self.species)
+    }
+}
+
+/// Kingdom of a species code
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Kingdom {
+    /// A
+    Archaea,
+    /// B
+    Bacteria,
+    /// E
+    Eukaryota,
+    /// V
+    Viruses,
+    /// O, others (synthetic, environmental samples, ...)
+    Other,
+}
+
+impl Kingdom {
+    /// Kingdom code used in `speclist.txt`
+    pub fn code(&self) -> char {
+        match self {
+            Kingdom::Archaea => 'A',
+            Kingdom::Bacteria => 'B',
+            Kingdom::Eukaryota => 'E',
+            Kingdom::Viruses => 'V',
+            Kingdom::Other => 'O',
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Kingdom> {
+        match code {
+            "A" => Some(Kingdom::Archaea),
+            "B" => Some(Kingdom::Bacteria),
+            "E" => Some(Kingdom::Eukaryota),
+            "V" => Some(Kingdom::Viruses),
+            "O" => Some(Kingdom::Other),
+            _ => None,
+        }
+    }
+}
+
+/// Species of the UniProt controlled vocabulary
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Species {
+    /// Species code (`BOVIN`)
+    pub code: String,
+    /// Kingdom
+    pub kingdom: Kingdom,
+    /// NCBI taxonomic identifier
+    pub taxon_id: String,
+    /// Official (scientific) name
+    pub official_name: String,
+    /// Common name
+    pub common_name: Option<String>,
+    /// Synonym
+    pub synonym: Option<String>,
+}
+
+/// Agreement between the species code of an entry name and the taxonomic identifier of the header
+#[derive(Debug, PartialEq, Clone)]
+pub enum SpeciesCheck {
+    /// Same taxon
+    Match,
+    /// Different taxon, with the taxonomic identifier of the species code
+    Mismatch(String),
+    /// Virtual code of a higher taxon: the identifier should be one of its descendants, which needs the taxonomy
+    Virtual(String),
+    /// Species code missing from the list
+    UnknownCode,
+    /// No taxonomic identifier to compare with (legacy headers)
+    MissingTaxon,
+}
+
+/// UniProt species list (`speclist.txt`)
+///
+/// ```rust
+/// let speclist: &[u8] = b"BOVIN E   9913: N=Bos taurus\n                 C=Bovine\n";
+///
+/// let species = uniprot_fasta_header::SpeciesList::read(speclist).unwrap();
+/// let name = "CASK_BOVIN".parse().unwrap();
+///
+/// assert_eq!(species.resolve(&name).unwrap().official_name, "Bos taurus");
+/// assert_eq!(species.check(&name, Some("9913")), uniprot_fasta_header::SpeciesCheck::Match);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct SpeciesList {
+    species: HashMap<String, Species>,
+    by_taxon: HashMap<String, String>,
+}
+
+impl SpeciesList {
+    /// Read a species list, lines other than species definitions are ignored
+    pub fn read<R: BufRead>(mut reader: R) -> Result<Self, UniProtHeaderError> {
+        static ENTRY: OnceCell<Regex> = OnceCell::new();
+        static NAME: OnceCell<Regex> = OnceCell::new();
+        let entry = ENTRY.get_or_init(|| {
+            Regex::new(r"^([A-Z0-9]{1,5}) +([ABEVO]) +([0-9]+): +N=(.*)$").unwrap()
+        });
+        let name = NAME.get_or_init(|| Regex::new(r"^ +([CS])=(.*)$").unwrap());
+
+        let mut list = SpeciesList::default();
+        let mut current: Option<Species> = None;
+        let mut buffer = Vec::new();
+
+        loop {
+            buffer.clear();
+            if reader.read_until(b'\n', &mut buffer)? == 0 {
+                break;
+            }
+            let line = String::from_utf8_lossy(trim_newline(&buffer)).to_string();
+
+            if let Some(c) = entry.captures(&line) {
+                if let Some(species) = current.take() {
+                    list.insert(species);
+                }
+                current = Some(Species {
+                    code: c[1].to_string(),
+                    kingdom: Kingdom::from_code(&c[2]).unwrap(),
+                    taxon_id: c[3].to_string(),
+                    official_name: c[4].trim().to_string(),
+                    common_name: None,
+                    synonym: None,
+                });
+            } else if let (Some(c), Some(species)) = (name.captures(&line), current.as_mut()) {
+                let value = Some(c[2].trim().to_string());
+                match &c[1] {
+                    "C" => species.common_name = value,
+                    _ => species.synonym = value,
+                }
+            } else if let Some(species) = current.take() {
+                list.insert(species);
+            }
+        }
+        if let Some(species) = current {
+            list.insert(species);
+        }
+
+        Ok(list)
+    }
+
+    fn insert(&mut self, species: Species) {
+        self.by_taxon
+            .entry(species.taxon_id.clone())
+            .or_insert_with(|| species.code.clone());
+        self.species.insert(species.code.clone(), species);
+    }
+
+    /// Species of a code
+    pub fn get(&self, code: &str) -> Option<&Species> {
+        self.species.get(code)
+    }
+
+    /// Species of a taxonomic identifier
+    pub fn by_taxon_id(&self, taxon_id: &str) -> Option<&Species> {
+        self.by_taxon.get(taxon_id).and_then(|code| self.get(code))
+    }
+
+    /// Species of an entry name
+    pub fn resolve(&self, entry_name: &EntryName) -> Option<&Species> {
+        self.get(&entry_name.species)
+    }
+
+    /// Check the species code of an entry name against a taxonomic identifier (`organism_identifier`)
+    pub fn check(&self, entry_name: &EntryName, taxon_id: Option<&str>) -> SpeciesCheck {
+        let species = match self.resolve(entry_name) {
+            Some(species) => species,
+            None => return SpeciesCheck::UnknownCode,
+        };
+        match taxon_id {
+            None => SpeciesCheck::MissingTaxon,
+            Some(taxon_id) if taxon_id == species.taxon_id => SpeciesCheck::Match,
+            Some(_) if entry_name.is_virtual() => SpeciesCheck::Virtual(species.taxon_id.clone()),
+            Some(_) => SpeciesCheck::Mismatch(species.taxon_id.clone()),
+        }
+    }
+
+    /// Number of species
+    pub fn len(&self) -> usize {
+        self.species.len()
+    }
+
+    /// Whether the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.species.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    const SPECLIST: &str = "\
+=======================================================================
+Code    Taxon    N=Official (scientific) name
+        Node     C=Common name
+                 S=Synonym
+_____ _ _______  ____________________________________________________________
+9HIV1 V   11676: N=Human immunodeficiency virus 1
+                 C=HIV-1
+BOVIN E    9913: N=Bos taurus
+                 C=Bovine
+ECOLI B   83333: N=Escherichia coli (strain K12)
+MACFA E    9541: N=Macaca fascicularis
+                 C=Crab-eating macaque
+                 S=Cynomolgus monkey
+
+=======================================================================
+";
+
+    #[test]
+    fn read() {
+        let list = SpeciesList::read(SPECLIST.as_bytes()).unwrap();
+        assert_eq!(list.len(), 4);
+        assert_eq!(
+            list.get("MACFA").unwrap(),
+            &Species {
+                code: "MACFA".to_string(),
+                kingdom: Kingdom::Eukaryota,
+                taxon_id: "9541".to_string(),
+                official_name: "Macaca fascicularis".to_string(),
+                common_name: Some("Crab-eating macaque".to_string()),
+                synonym: Some("Cynomolgus monkey".to_string()),
+            }
+        );
+        assert_eq!(list.by_taxon_id("83333").unwrap().code, "ECOLI");
+    }
+
+    #[test_case("CASK_BOVIN", Some("9913"), SpeciesCheck::Match ; "same taxon")]
+    #[test_case("YPFU_ECOLI", Some("562"), SpeciesCheck::Mismatch("83333".to_string()) ; "mismatch")]
+    #[test_case("Q3SA23_9HIV1", Some("11706"), SpeciesCheck::Virtual("11676".to_string()) ; "virtual code")]
+    #[test_case("AATM_RABIT", Some("9986"), SpeciesCheck::UnknownCode ; "unknown code")]
+    #[test_case("CASK_BOVIN", None, SpeciesCheck::MissingTaxon ; "legacy")]
+    fn check(entry_name: &str, taxon_id: Option<&str>, expected: SpeciesCheck) {
+        let list = SpeciesList::read(SPECLIST.as_bytes()).unwrap();
+        let entry_name: EntryName = entry_name.parse().unwrap();
+        pretty_assertions::assert_eq!(list.check(&entry_name, taxon_id), expected);
+    }
+
+    #[test_case("CASK_BOVIN", true ; "swiss-prot")]
+    #[test_case("A0A023GPI8_CANAL", true ; "trembl")]
+    #[test_case("CASK", false ; "no species")]
+    #[test_case("CASK_", false ; "empty species")]
+    #[test_case("CASK_BOVINE", false ; "species too long")]
+    fn entry_names(input: &str, valid: bool) {
+        pretty_assertions::assert_eq!(input.parse::<EntryName>().is_ok(), valid);
+    }
+}
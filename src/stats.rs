@@ -0,0 +1,284 @@
+use crate::{
+    error::UniProtHeaderError,
+    fasta::{FastaReader, Record},
+    uniprotkb, Database, ProteinExistence, UniProtKB,
+};
+use std::{collections::BTreeMap, io::BufRead};
+
+/// Residues outside of the 20 standard amino acids
+pub const NON_STANDARD_RESIDUES: [char; 5] = ['U', 'O', 'X', 'B', 'Z'];
+
+// Average mass of water, added once per chain
+const WATER: f64 = 18.01524;
+
+// Average residue masses in daltons, B and Z are averaged over their two amino acids
+fn residue_mass(residue: char) -> Option<f64> {
+    match residue {
+        'A' => Some(71.0788),
+        'R' => Some(156.1875),
+        'N' => Some(114.1038),
+        'D' => Some(115.0886),
+        'C' => Some(103.1388),
+        'E' => Some(129.1155),
+        'Q' => Some(128.1307),
+        'G' => Some(57.0519),
+        'H' => Some(137.1411),
+        'I' => Some(113.1594),
+        'L' => Some(113.1594),
+        'K' => Some(128.1741),
+        'M' => Some(131.1926),
+        'F' => Some(147.1766),
+        'P' => Some(97.1167),
+        'S' => Some(87.0782),
+        'T' => Some(101.1051),
+        'W' => Some(186.2132),
+        'Y' => Some(163.1760),
+        'V' => Some(99.1326),
+        'U' => Some(150.0388),
+        'O' => Some(237.3018),
+        'B' => Some((114.1038 + 115.0886) / 2.0),
+        'Z' => Some((128.1307 + 129.1155) / 2.0),
+        _ => None,
+    }
+}
+
+/// Statistics of a protein sequence
+///
+/// ```rust
+/// let stats = uniprot_fasta_header::SequenceStats::new("MKUG*");
+///
+/// assert_eq!(stats.length, 4);
+/// assert!(stats.terminal_stop);
+/// assert_eq!(stats.non_standard_residues(), vec!['U']);
+/// ```
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SequenceStats {
+    /// Number of residues, without the terminal stop
+    pub length: usize,
+    /// Number of each residue, in uppercase
+    pub composition: BTreeMap<char, usize>,
+    /// Average molecular weight in daltons, X and unknown characters are left out
+    pub molecular_weight: f64,
+    /// The sequence ends with a stop (`*`)
+    pub terminal_stop: bool,
+}
+
+impl SequenceStats {
+    /// Statistics of a sequence
+    pub fn new(sequence: &str) -> Self {
+        let (residues, terminal_stop) = match sequence.strip_suffix('*') {
+            Some(residues) => (residues, true),
+            None => (sequence, false),
+        };
+
+        let mut stats = SequenceStats {
+            terminal_stop,
+            ..SequenceStats::default()
+        };
+        for residue in residues.chars().map(|c| c.to_ascii_uppercase()) {
+            *stats.composition.entry(residue).or_default() += 1;
+            stats.length += 1;
+            stats.molecular_weight += residue_mass(residue).unwrap_or_default();
+        }
+        if stats.length > 0 {
+            stats.molecular_weight += WATER;
+        }
+        stats
+    }
+
+    /// Number of occurrences of a residue
+    pub fn count(&self, residue: char) -> usize {
+        self.composition
+            .get(&residue.to_ascii_uppercase())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Non-standard residues (U, O, X, B, Z) found in the sequence
+    pub fn non_standard_residues(&self) -> Vec<char> {
+        NON_STANDARD_RESIDUES
+            .iter()
+            .copied()
+            .filter(|&residue| self.count(residue) > 0)
+            .collect()
+    }
+
+    /// The sequence has non-standard residues
+    pub fn has_non_standard(&self) -> bool {
+        !self.non_standard_residues().is_empty()
+    }
+}
+
+impl Record {
+    /// Statistics of the sequence
+    pub fn stats(&self) -> SequenceStats {
+        SequenceStats::new(&self.sequence)
+    }
+}
+
+/// Statistics of a group of sequences
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SequenceSummary {
+    /// Number of sequences
+    pub records: usize,
+    /// Total number of residues
+    pub residues: usize,
+    /// Shortest sequence
+    pub min_length: usize,
+    /// Longest sequence
+    pub max_length: usize,
+    /// Number of each residue
+    pub composition: BTreeMap<char, usize>,
+    /// Sequences with non-standard residues
+    pub with_non_standard: usize,
+    /// Sequences ending with a stop
+    pub with_terminal_stop: usize,
+}
+
+impl SequenceSummary {
+    /// Add the statistics of a sequence
+    pub fn add(&mut self, stats: &SequenceStats) {
+        if self.records == 0 || stats.length < self.min_length {
+            self.min_length = stats.length;
+        }
+        self.max_length = self.max_length.max(stats.length);
+        self.records += 1;
+        self.residues += stats.length;
+        for (&residue, &count) in &stats.composition {
+            *self.composition.entry(residue).or_default() += count;
+        }
+        if stats.has_non_standard() {
+            self.with_non_standard += 1;
+        }
+        if stats.terminal_stop {
+            self.with_terminal_stop += 1;
+        }
+    }
+
+    /// Mean sequence length, 0 without sequences
+    pub fn mean_length(&self) -> f64 {
+        if self.records == 0 {
+            0.0
+        } else {
+            self.residues as f64 / self.records as f64
+        }
+    }
+}
+
+/// Sequence statistics of a UniProtKB file, by database and by protein existence
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StatsSummary {
+    /// All sequences
+    pub total: SequenceSummary,
+    /// Sequences by database
+    pub by_database: BTreeMap<Database, SequenceSummary>,
+    /// Sequences by protein existence
+    pub by_protein_existence: BTreeMap<ProteinExistence, SequenceSummary>,
+}
+
+impl StatsSummary {
+    /// Add the statistics of the sequence of a header
+    pub fn add(&mut self, header: &UniProtKB, stats: &SequenceStats) {
+        self.total.add(stats);
+        self.by_database
+            .entry(header.database.clone())
+            .or_default()
+            .add(stats);
+        self.by_protein_existence
+            .entry(header.protein_existence.clone())
+            .or_default()
+            .add(stats);
+    }
+}
+
+/// Sequence statistics of a UniProtKB FASTA file, fails on other headers
+///
+/// ```rust
+/// use uniprot_fasta_header::{Database, ProteinExistence};
+///
+/// let fasta: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKSF\n>tr|A0A023GPI8|A0A023GPI8_CANAL Lectin OS=Canavalia lineata OX=28957 PE=4 SV=1\nADTIVAVX\n";
+///
+/// let summary = uniprot_fasta_header::summarize_fasta(fasta).unwrap();
+///
+/// assert_eq!(summary.total.records, 2);
+/// assert_eq!(summary.by_database[&Database::TrEMBL].with_non_standard, 1);
+/// assert_eq!(
+///     summary.by_protein_existence[&ProteinExistence::ExperimentalEvidenceProtein].max_length,
+///     5
+/// );
+/// ```
+pub fn summarize_fasta<R: BufRead>(reader: R) -> Result<StatsSummary, UniProtHeaderError> {
+    let mut summary = StatsSummary::default();
+    for record in FastaReader::new(reader) {
+        let record = record?;
+        let header = uniprotkb(record.header.as_bytes())?;
+        summary.add(&header, &record.stats());
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case("G", 75.06714 ; "glycine")]
+    #[test_case("GA", 146.14594 ; "dipeptide")]
+    #[test_case("GX*", 75.06714 ; "unknown residue and stop")]
+    #[test_case("", 0.0 ; "empty")]
+    fn molecular_weight(sequence: &str, expected: f64) {
+        let stats = SequenceStats::new(sequence);
+        assert!((stats.molecular_weight - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn stats() {
+        let stats = SequenceStats::new("mkkAXbz*");
+        assert_eq!(stats.length, 7);
+        assert_eq!(stats.count('K'), 2);
+        assert_eq!(stats.count('k'), 2);
+        assert!(stats.terminal_stop);
+        assert!(stats.has_non_standard());
+        assert_eq!(stats.non_standard_residues(), vec!['X', 'B', 'Z']);
+        assert!(!SequenceStats::new("MKV").has_non_standard());
+    }
+
+    #[test]
+    fn summary() {
+        let fasta = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1
+MMKSF
+>sp|P12345|AATM_RABIT Aspartate aminotransferase OS=Oryctolagus cuniculus OX=9986 GN=GOT2 PE=1 SV=2
+MALLHSGRVLSGVASAFHPGLAAAASARASSWWAHVEMGPPDPILGVTEAFKRDTNSKKMNLGVGAYRDDNGKPYVLPSVRKAEAQIAAKGLDKEYLPIGGLAEFCRASAELALGENSEVVKSGRFVTVQTISGTGALRIGASFLQRFFKFSRDVFLPKPSWGNHTPIFRDAGMQLQSYRYYDPKTCGFDFTGALEDISKIPEQSVLLLHACAHNPTGVDPRPEQWKEIATVVKKRNLFAFFDMAYQGFASGDGDKDAWAVRHFIEQGINVCLCQSYAKNMGLYGERVGAFTVICKDADEAKRVESQLKILIRPMYSNPPIHGARIASTILTSPDLRKQWLQEVKGMADRIIGMRTQLVSNLKKEGSTHSWQHITDQIGMFCFTGLKPEQVERLTKEFSIYMTKDGRISVAGVTSGNVGYLAHAIHQVTK
+>tr|A0A023GPI8|A0A023GPI8_CANAL Lectin OS=Canavalia lineata OX=28957 PE=4 SV=1
+ADTIVAVELDTYPNTDIGDPSYPHIGIDIKSVRSKKTAKWNMQNGKVGTAHIIYNSVDKRLSAVVSYPNADSATVSYDVDLDNVLPEWVRVGLSASTGLYKETNTILSWSFTSKLKSNSTHETNALHFMFNQFSKDQKDLILQGDATTGTDGNLELTRVSSNGSPQGSSVGRALFYAPVHIWESSAVVASFEATFTFLIKSPDSHPADGIAFFISNIDSSIPSGSTGRLLGLFPDAN*
+";
+        let summary = summarize_fasta(&fasta[..]).unwrap();
+        assert_eq!(summary.total.records, 3);
+        assert_eq!(summary.total.min_length, 5);
+        assert_eq!(summary.total.with_terminal_stop, 1);
+        assert_eq!(summary.by_database[&Database::SwissProt].records, 2);
+        assert_eq!(summary.by_database[&Database::TrEMBL].max_length, 237);
+        assert_eq!(
+            summary.by_protein_existence[&ProteinExistence::ExperimentalEvidenceProtein].records,
+            2
+        );
+        assert_eq!(
+            summary.by_protein_existence[&ProteinExistence::Predicted].with_terminal_stop,
+            1
+        );
+        assert_eq!(
+            summary.by_database[&Database::SwissProt].composition[&'M'],
+            13
+        );
+        assert!((SequenceSummary::default().mean_length() - 0.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn not_uniprotkb() {
+        assert!(summarize_fasta(&b">WP_003131952.1 30S ribosomal protein S18\nMK\n"[..]).is_err());
+    }
+}
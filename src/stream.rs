@@ -0,0 +1,248 @@
+use crate::error::UniProtHeaderError;
+use crate::filter::trim_newline;
+use memchr::memchr;
+
+#[cfg(feature = "tokio")]
+use std::collections::VecDeque;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+/// Push based header parser
+///
+/// Bytes are fed in chunks of any size. Partial header lines are kept until their newline arrives,
+/// sequence lines are dropped without being buffered. Errors carry the line number (starting at 1).
+///
+/// ```rust
+/// let mut stream = uniprot_fasta_header::StreamParser::new(uniprot_fasta_header::uniprotkb);
+///
+/// assert!(stream.feed(b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos").is_empty());
+/// let parsed = stream.feed(b" taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKS\n");
+/// assert_eq!(parsed[0].as_ref().unwrap().entry_name, "CASK_BOVIN");
+///
+/// assert!(stream.finish().is_none());
+/// ```
+#[derive(Debug)]
+pub struct StreamParser<F> {
+    parser: F,
+    buffer: Vec<u8>,
+    line: usize,
+    line_start: bool,
+    header: bool,
+}
+
+impl<T, F> StreamParser<F>
+where
+    F: Fn(&[u8]) -> Result<T, UniProtHeaderError>,
+{
+    /// New stream parser using one of the header parsers
+    pub fn new(parser: F) -> Self {
+        Self {
+            parser,
+            buffer: Vec::new(),
+            line: 1,
+            line_start: true,
+            header: false,
+        }
+    }
+
+    /// Feed the next chunk of bytes and get the headers completed by it, in order
+    pub fn feed(&mut self, mut chunk: &[u8]) -> Vec<Result<T, UniProtHeaderError>> {
+        let mut parsed = Vec::new();
+
+        while !chunk.is_empty() {
+            if self.line_start {
+                self.header = chunk[0] == b'>';
+                self.line_start = false;
+            }
+
+            let (part, end_of_line) = match memchr(b'\n', chunk) {
+                Some(i) => (&chunk[..=i], true),
+                None => (chunk, false),
+            };
+            chunk = &chunk[part.len()..];
+
+            if self.header {
+                self.buffer.extend_from_slice(part);
+            }
+
+            if end_of_line {
+                if self.header {
+                    parsed.push(self.parse_buffer().map_err(|e| e.at_line(self.line)));
+                }
+                self.line += 1;
+                self.line_start = true;
+            }
+        }
+
+        parsed
+    }
+
+    /// End of the stream: parse the last header if it was not followed by a newline
+    ///
+    /// That header line was never terminated, so any failure to parse it is reported as
+    /// [`UniProtHeaderError::Incomplete`]: the stream most likely stopped in the middle of it.
+    /// When the input is known to be complete, feed a final `b"\n"` first to get the parser's own error.
+    pub fn finish(mut self) -> Option<Result<T, UniProtHeaderError>> {
+        if self.header && !self.buffer.is_empty() {
+            let line = self.line;
+            Some(
+                self.parse_buffer()
+                    .map_err(|_| UniProtHeaderError::Incomplete.at_line(line)),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Whether a header line has been started but not finished yet
+    pub fn is_partial(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+
+    fn parse_buffer(&mut self) -> Result<T, UniProtHeaderError> {
+        let parsed = (self.parser)(trim_newline(&self.buffer));
+        self.buffer.clear();
+        parsed
+    }
+}
+
+/// Asynchronous header reader on top of a [`tokio::io::AsyncBufRead`]
+///
+/// ```rust
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let fasta: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKS\n";
+///
+/// let mut reader = uniprot_fasta_header::AsyncHeaderReader::new(fasta, uniprot_fasta_header::uniprotkb);
+///
+/// while let Some(header) = reader.next_header().await {
+///     assert_eq!(header.unwrap().entry_name, "CASK_BOVIN");
+/// }
+/// # });
+/// ```
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct AsyncHeaderReader<R, F, T> {
+    reader: R,
+    stream: Option<StreamParser<F>>,
+    ready: VecDeque<Result<T, UniProtHeaderError>>,
+}
+
+#[cfg(feature = "tokio")]
+impl<R, T, F> AsyncHeaderReader<R, F, T>
+where
+    R: AsyncBufRead + Unpin,
+    F: Fn(&[u8]) -> Result<T, UniProtHeaderError>,
+{
+    /// New asynchronous reader using one of the header parsers
+    pub fn new(reader: R, parser: F) -> Self {
+        Self {
+            reader,
+            stream: Some(StreamParser::new(parser)),
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Next parsed header, `None` at the end of the stream
+    pub async fn next_header(&mut self) -> Option<Result<T, UniProtHeaderError>> {
+        loop {
+            if let Some(parsed) = self.ready.pop_front() {
+                return Some(parsed);
+            }
+
+            let stream = self.stream.as_mut()?;
+            let chunk = match self.reader.fill_buf().await {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    self.stream = None;
+                    return Some(Err(e.into()));
+                }
+            };
+
+            if chunk.is_empty() {
+                return self.stream.take().and_then(StreamParser::finish);
+            }
+
+            let length = chunk.len();
+            self.ready.extend(stream.feed(chunk));
+            self.reader.consume(length);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uniprotkb, uniprotkb_iso};
+    use pretty_assertions::assert_eq;
+
+    const FASTA: &[u8] = b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKS\nFLLV\n>sp|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2\nMAEE\n";
+
+    #[test]
+    fn any_chunk_size() {
+        for size in 1..FASTA.len() {
+            let mut stream = StreamParser::new(uniprotkb);
+            let parsed: Vec<_> = FASTA.chunks(size).flat_map(|c| stream.feed(c)).collect();
+            assert_eq!(parsed.len(), 2);
+            assert!(parsed[0].is_ok());
+            assert!(matches!(parsed[1], Err(UniProtHeaderError::Line(4, _))));
+            assert!(stream.finish().is_none());
+        }
+    }
+
+    #[test]
+    fn sequence_lines_are_not_buffered() {
+        let mut stream = StreamParser::new(uniprotkb);
+        stream.feed(
+            b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1\nMMKS",
+        );
+        assert!(!stream.is_partial());
+    }
+
+    #[test]
+    fn last_line_without_newline() {
+        let mut stream = StreamParser::new(uniprotkb_iso);
+        assert!(stream.feed(&FASTA[..FASTA.len() - 6]).len() == 1);
+        assert!(stream.finish().unwrap().is_ok());
+    }
+
+    #[test]
+    fn truncated_header() {
+        let mut stream = StreamParser::new(uniprotkb);
+        stream.feed(b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos");
+        assert_eq!(
+            stream.finish().unwrap().unwrap_err(),
+            UniProtHeaderError::Incomplete.at_line(1)
+        );
+    }
+
+    #[test]
+    fn truncated_in_protein_name() {
+        let mut stream = StreamParser::new(uniprotkb);
+        stream.feed(b">sp|P02668|CASK_BOVIN Kappa-cas");
+        assert_eq!(
+            stream.finish().unwrap().unwrap_err(),
+            UniProtHeaderError::Incomplete.at_line(1)
+        );
+    }
+
+    #[test]
+    fn malformed_last_header() {
+        let header = b">xx|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1";
+
+        let mut stream = StreamParser::new(uniprotkb);
+        stream.feed(header);
+        assert_eq!(
+            stream.finish().unwrap().unwrap_err(),
+            UniProtHeaderError::Incomplete.at_line(1)
+        );
+
+        let mut stream = StreamParser::new(uniprotkb);
+        stream.feed(header);
+        let parsed = stream.feed(b"\n");
+        assert!(matches!(
+            &parsed[0],
+            Err(UniProtHeaderError::Line(1, e)) if matches!(**e, UniProtHeaderError::ParsingError(..))
+        ));
+        assert!(stream.finish().is_none());
+    }
+}
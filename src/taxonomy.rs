@@ -0,0 +1,328 @@
+use crate::{
+    error::UniProtHeaderError, filter::trim_newline, format::parse_any, header::FastaHeader,
+};
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+};
+
+/// NCBI taxonomy tree, loaded from a local taxdump (`nodes.dmp` and `names.dmp`)
+///
+/// ```rust
+/// let nodes: &[u8] = b"1\t|\t1\t|\tno rank\t|\n2\t|\t1\t|\tsuperkingdom\t|\n562\t|\t2\t|\tspecies\t|\n";
+/// let names: &[u8] = b"2\t|\tBacteria\t|\tBacteria <bacteria>\t|\tscientific name\t|\n562\t|\tEscherichia coli\t|\t\t|\tscientific name\t|\n";
+///
+/// let taxonomy = uniprot_fasta_header::Taxonomy::read(nodes, names).unwrap();
+///
+/// assert!(taxonomy.is_descendant(562, 2));
+/// assert_eq!(taxonomy.lineage(562), vec![562, 2, 1]);
+/// assert_eq!(taxonomy.rank(2), Some("superkingdom"));
+/// assert_eq!(taxonomy.id_of("Escherichia coli"), Some(562));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Taxonomy {
+    // taxon -> (parent, rank index)
+    nodes: HashMap<u32, (u32, u16)>,
+    ranks: Vec<String>,
+    names: HashMap<u32, String>,
+    by_name: HashMap<String, u32>,
+}
+
+// Fields of a .dmp line, separated by `\t|\t` and ended by `\t|`
+fn dmp_fields(line: &str) -> Vec<&str> {
+    let line = line.strip_suffix("\t|").unwrap_or(line);
+    line.split("\t|\t").collect()
+}
+
+fn dmp_error(line: &str, message: &str, number: usize) -> UniProtHeaderError {
+    UniProtHeaderError::ParsingError(line.to_string(), message.to_string()).at_line(number)
+}
+
+fn for_each_line<R: BufRead, F>(mut reader: R, mut f: F) -> Result<(), UniProtHeaderError>
+where
+    F: FnMut(&str, usize) -> Result<(), UniProtHeaderError>,
+{
+    let mut buffer = Vec::new();
+    let mut number = 0;
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            return Ok(());
+        }
+        number += 1;
+        let line = String::from_utf8_lossy(trim_newline(&buffer));
+        if !line.is_empty() {
+            f(&line, number)?;
+        }
+    }
+}
+
+impl Taxonomy {
+    /// Read `nodes.dmp` and `names.dmp`, only scientific names are kept
+    pub fn read<N: BufRead, M: BufRead>(nodes: N, names: M) -> Result<Self, UniProtHeaderError> {
+        let mut taxonomy = Taxonomy::default();
+        let mut rank_index: HashMap<String, u16> = HashMap::new();
+
+        for_each_line(nodes, |line, number| {
+            let fields = dmp_fields(line);
+            let (taxon, parent) = match (
+                fields.first().and_then(|f| f.trim().parse::<u32>().ok()),
+                fields.get(1).and_then(|f| f.trim().parse::<u32>().ok()),
+            ) {
+                (Some(taxon), Some(parent)) => (taxon, parent),
+                _ => return Err(dmp_error(line, "invalid nodes.dmp line", number)),
+            };
+            let rank = fields.get(2).map_or("no rank", |f| f.trim());
+            let index = match rank_index.get(rank) {
+                Some(&index) => index,
+                None => {
+                    let index = taxonomy.ranks.len() as u16;
+                    taxonomy.ranks.push(rank.to_string());
+                    rank_index.insert(rank.to_string(), index);
+                    index
+                }
+            };
+            taxonomy.nodes.insert(taxon, (parent, index));
+            Ok(())
+        })?;
+
+        for_each_line(names, |line, number| {
+            let fields = dmp_fields(line);
+            if fields.len() < 4 {
+                return Err(dmp_error(line, "invalid names.dmp line", number));
+            }
+            if fields[3].trim() != "scientific name" {
+                return Ok(());
+            }
+            let taxon = match fields[0].trim().parse::<u32>() {
+                Ok(taxon) => taxon,
+                Err(_) => return Err(dmp_error(line, "invalid names.dmp line", number)),
+            };
+            let name = fields[1].trim().to_string();
+            let unique = fields[2].trim();
+            if !unique.is_empty() {
+                taxonomy.by_name.insert(unique.to_string(), taxon);
+            }
+            taxonomy.by_name.entry(name.clone()).or_insert(taxon);
+            taxonomy.names.insert(taxon, name);
+            Ok(())
+        })?;
+
+        Ok(taxonomy)
+    }
+
+    /// Read `nodes.dmp` and `names.dmp` from a taxdump directory
+    pub fn open<P: AsRef<Path>>(directory: P) -> Result<Self, UniProtHeaderError> {
+        let directory = directory.as_ref();
+        let nodes = BufReader::new(File::open(directory.join("nodes.dmp"))?);
+        let names = BufReader::new(File::open(directory.join("names.dmp"))?);
+        Self::read(nodes, names)
+    }
+
+    /// Number of taxa
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the taxonomy is empty
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Parent taxon, `None` for the root and unknown taxa
+    pub fn parent(&self, taxon: u32) -> Option<u32> {
+        match self.nodes.get(&taxon) {
+            Some(&(parent, _)) if parent != taxon => Some(parent),
+            _ => None,
+        }
+    }
+
+    /// Rank of a taxon (`species`, `genus`, `order`, ...)
+    pub fn rank(&self, taxon: u32) -> Option<&str> {
+        self.nodes
+            .get(&taxon)
+            .map(|&(_, rank)| self.ranks[rank as usize].as_str())
+    }
+
+    /// Scientific name of a taxon
+    pub fn name(&self, taxon: u32) -> Option<&str> {
+        self.names.get(&taxon).map(String::as_str)
+    }
+
+    /// Taxon of a scientific name, or of a unique name (`Bacteria <bacteria>`)
+    pub fn id_of(&self, name: &str) -> Option<u32> {
+        self.by_name.get(name).copied()
+    }
+
+    /// Taxon and its ancestors up to the root, empty for unknown taxa
+    pub fn lineage(&self, taxon: u32) -> Vec<u32> {
+        let mut lineage = Vec::new();
+        if !self.nodes.contains_key(&taxon) {
+            return lineage;
+        }
+        let mut current = Some(taxon);
+        while let Some(t) = current {
+            // a malformed dump could loop
+            if lineage.len() > self.nodes.len() {
+                break;
+            }
+            lineage.push(t);
+            current = self.parent(t);
+        }
+        lineage
+    }
+
+    /// Whether `taxon` is `ancestor` or one of its descendants
+    pub fn is_descendant(&self, taxon: u32, ancestor: u32) -> bool {
+        self.lineage(taxon).contains(&ancestor)
+    }
+
+    /// Ancestor of a taxon at a given rank, the taxon itself included
+    pub fn ancestor_at_rank(&self, taxon: u32, rank: &str) -> Option<u32> {
+        self.lineage(taxon)
+            .into_iter()
+            .find(|&t| self.rank(t) == Some(rank))
+    }
+
+    /// Taxon of a parsed header (`OX=`, `TaxID=`)
+    pub fn taxon_of<H: FastaHeader + ?Sized>(header: &H) -> Option<u32> {
+        header.taxon_id().and_then(|t| t.parse().ok())
+    }
+
+    /// Whether a parsed header belongs to one of the clades
+    pub fn header_in_clades<H: FastaHeader + ?Sized>(&self, header: &H, clades: &[u32]) -> bool {
+        match Self::taxon_of(header) {
+            Some(taxon) => {
+                let lineage = self.lineage(taxon);
+                clades.iter().any(|c| lineage.contains(c))
+            }
+            None => false,
+        }
+    }
+}
+
+/// Copy the FASTA records whose header taxon belongs to one of the clades
+///
+/// Records are copied byte for byte. Headers of any format are parsed, those without taxon never match.
+/// Returns the number of records written.
+pub fn filter_fasta_by_taxon<R: BufRead, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    taxonomy: &Taxonomy,
+    clades: &[u32],
+) -> Result<usize, UniProtHeaderError> {
+    let mut line = Vec::new();
+    let mut keep = false;
+    let mut counter: usize = 0;
+
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+
+        if line.starts_with(b">") {
+            keep = match parse_any(trim_newline(&line)) {
+                Ok(parsed) => taxonomy.header_in_clades(&parsed, clades),
+                Err(_) => false,
+            };
+            if keep {
+                counter += 1;
+            }
+        }
+
+        if keep {
+            writer.write_all(&line)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(counter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uniprotkb;
+    use pretty_assertions::assert_eq;
+
+    const NODES: &str = "1\t|\t1\t|\tno rank\t|\t\t|
+2\t|\t131567\t|\tsuperkingdom\t|\t\t|
+131567\t|\t1\t|\tno rank\t|\t\t|
+91347\t|\t1236\t|\torder\t|\t\t|
+1236\t|\t1224\t|\tclass\t|\t\t|
+1224\t|\t2\t|\tphylum\t|\t\t|
+543\t|\t91347\t|\tfamily\t|\t\t|
+561\t|\t543\t|\tgenus\t|\t\t|
+562\t|\t561\t|\tspecies\t|\t\t|
+83333\t|\t562\t|\tstrain\t|\t\t|
+9606\t|\t9605\t|\tspecies\t|\t\t|
+9605\t|\t131567\t|\tgenus\t|\t\t|
+";
+
+    const NAMES: &str = "2\t|\tBacteria\t|\tBacteria <bacteria>\t|\tscientific name\t|
+2\t|\teubacteria\t|\t\t|\tgenbank common name\t|
+91347\t|\tEnterobacterales\t|\t\t|\tscientific name\t|
+562\t|\tEscherichia coli\t|\t\t|\tscientific name\t|
+9606\t|\tHomo sapiens\t|\t\t|\tscientific name\t|
+";
+
+    fn taxonomy() -> Taxonomy {
+        Taxonomy::read(NODES.as_bytes(), NAMES.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn tree() {
+        let taxonomy = taxonomy();
+        assert_eq!(taxonomy.len(), 12);
+        assert_eq!(
+            taxonomy.lineage(83333),
+            vec![83333, 562, 561, 543, 91347, 1236, 1224, 2, 131567, 1]
+        );
+        assert!(taxonomy.is_descendant(83333, 91347));
+        assert!(taxonomy.is_descendant(562, 562));
+        assert!(!taxonomy.is_descendant(9606, 2));
+        assert_eq!(taxonomy.ancestor_at_rank(83333, "order"), Some(91347));
+        assert_eq!(taxonomy.parent(1), None);
+        assert!(taxonomy.lineage(7).is_empty());
+    }
+
+    #[test]
+    fn names() {
+        let taxonomy = taxonomy();
+        assert_eq!(taxonomy.id_of("Enterobacterales"), Some(91347));
+        assert_eq!(taxonomy.id_of("Bacteria <bacteria>"), Some(2));
+        assert_eq!(taxonomy.id_of("eubacteria"), None);
+        assert_eq!(taxonomy.name(9606), Some("Homo sapiens"));
+    }
+
+    #[test]
+    fn headers() {
+        let taxonomy = taxonomy();
+        let header = uniprotkb(b">sp|P18355|YPFU_ECOLI Uncharacterized protein OS=Escherichia coli (strain K12) OX=83333 PE=3 SV=1").unwrap();
+        assert_eq!(Taxonomy::taxon_of(&header), Some(83333));
+        assert!(taxonomy.header_in_clades(&header, &[9606, 91347]));
+        assert!(!taxonomy.header_in_clades(&header, &[9606]));
+    }
+
+    #[test]
+    fn filter() {
+        let fasta = b">sp|P18355|YPFU_ECOLI Uncharacterized protein OS=Escherichia coli (strain K12) OX=83333 PE=3 SV=1\nMK\n>sp|P02668|CASK_HUMAN Kappa-casein OS=Homo sapiens OX=9606 GN=CSN3 PE=1 SV=1\nMM\n>NP_000005.3 alpha-2-macroglobulin [Homo sapiens]\nMG\n";
+        let mut out = Vec::new();
+        let kept = filter_fasta_by_taxon(&fasta[..], &mut out, &taxonomy(), &[2]).unwrap();
+        assert_eq!(kept, 1);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            ">sp|P18355|YPFU_ECOLI Uncharacterized protein OS=Escherichia coli (strain K12) OX=83333 PE=3 SV=1\nMK\n"
+        );
+    }
+
+    #[test]
+    fn invalid_dump() {
+        let err =
+            Taxonomy::read(&b"1\t|\t1\t|\tno rank\t|\nx\t|\t1\t|\n"[..], &b""[..]).unwrap_err();
+        assert!(matches!(err, UniProtHeaderError::Line(2, _)));
+    }
+}
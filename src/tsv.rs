@@ -0,0 +1,463 @@
+use crate::{
+    error::UniProtHeaderError,
+    fasta::Record,
+    filter::trim_newline,
+    header::FastaHeader,
+    parser::{scientific_name, trailing_group},
+    Database, ProteinExistence, UniProtKB,
+};
+use std::{collections::HashMap, io::BufRead};
+
+/// Row of a UniProt tabular export
+///
+/// Known columns are mapped to the fields of the [`UniProtKB`] header, columns absent from the download are `None`.
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TsvRow {
+    /// `Entry`
+    pub accession: String,
+    /// `Entry Name`
+    pub entry_name: Option<String>,
+    /// `Reviewed`: Swiss-Prot or TrEMBL
+    pub database: Option<Database>,
+    /// `Protein names`: recommended name, with `(Fragment)` for fragments
+    pub protein_name: Option<String>,
+    /// `Gene Names (primary)`, or the first of `Gene Names`
+    pub gene_name: Option<String>,
+    /// `Organism`: scientific name
+    pub organism_name: Option<String>,
+    /// `Organism (ID)`
+    pub organism_identifier: Option<String>,
+    /// `Protein existence`
+    pub protein_existence: Option<ProteinExistence>,
+    /// `Sequence version`
+    pub sequence_version: Option<String>,
+    /// `Sequence`
+    pub sequence: Option<String>,
+    /// Other columns, with their names
+    pub extras: Vec<(String, String)>,
+}
+
+impl TsvRow {
+    /// Value of an unknown column
+    pub fn extra(&self, column: &str) -> Option<&str> {
+        self.extras
+            .iter()
+            .find(|(name, _)| name == column)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// UniProtKB header of the row
+    ///
+    /// Fails if the download lacks the columns of the header fields: `Entry Name`, `Reviewed`, `Protein names`,
+    /// `Organism`, `Protein existence` and `Sequence version`.
+    pub fn to_uniprotkb(&self) -> Result<UniProtKB, UniProtHeaderError> {
+        let missing = |column: &str| {
+            UniProtHeaderError::ParsingError(
+                self.accession.clone(),
+                format!("missing column {}", column),
+            )
+        };
+        Ok(UniProtKB {
+            database: self.database.clone().ok_or_else(|| missing("Reviewed"))?,
+            identifier: self.accession.clone(),
+            entry_name: self
+                .entry_name
+                .clone()
+                .ok_or_else(|| missing("Entry Name"))?,
+            protein_name: self
+                .protein_name
+                .clone()
+                .ok_or_else(|| missing("Protein names"))?,
+            organism_name: self
+                .organism_name
+                .clone()
+                .ok_or_else(|| missing("Organism"))?,
+            organism_identifier: self.organism_identifier.clone(),
+            gene_name: self.gene_name.clone(),
+            protein_existence: self
+                .protein_existence
+                .clone()
+                .ok_or_else(|| missing("Protein existence"))?,
+            sequence_version: self
+                .sequence_version
+                .clone()
+                .ok_or_else(|| missing("Sequence version"))?,
+        })
+    }
+
+    /// FASTA record of the row, with the `Sequence` column
+    pub fn to_record(&self) -> Result<Record, UniProtHeaderError> {
+        Ok(Record {
+            header: self.to_uniprotkb()?.to_string(),
+            sequence: self.sequence.clone().unwrap_or_default(),
+        })
+    }
+}
+
+// First name of `Gene Names`, synonyms are separated by spaces and genes by `; `
+fn first_gene_name(names: &str) -> Option<&str> {
+    names
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find(|name| !name.is_empty())
+}
+
+// Recommended name of `Protein names`, the alternative names and the components (`[Cleaved into: ...]`) are removed
+fn recommended_name(names: &str) -> String {
+    let mut name = names.trim();
+    let mut fragment = None;
+    while let Some(start) = trailing_group(name) {
+        if start == 0 || !name[..start].ends_with(' ') {
+            break;
+        }
+        let group = &name[start + 1..name.len() - 1];
+        if group == "Fragment" || group == "Fragments" {
+            fragment = Some(group);
+        }
+        name = name[..start].trim_end();
+    }
+    match fragment {
+        Some(fragment) => format!("{} ({})", name, fragment),
+        None => name.to_string(),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Column {
+    Accession,
+    EntryName,
+    Reviewed,
+    ProteinNames,
+    GeneNames,
+    PrimaryGeneName,
+    Organism,
+    OrganismIdentifier,
+    ProteinExistence,
+    SequenceVersion,
+    Sequence,
+    Extra,
+}
+
+impl Column {
+    // Current and legacy column names
+    fn from_name(name: &str) -> Column {
+        match name {
+            "Entry" => Column::Accession,
+            "Entry Name" | "Entry name" => Column::EntryName,
+            "Reviewed" | "Status" => Column::Reviewed,
+            "Protein names" => Column::ProteinNames,
+            "Gene Names" | "Gene names" => Column::GeneNames,
+            "Gene Names (primary)" | "Gene names  (primary )" => Column::PrimaryGeneName,
+            "Organism" => Column::Organism,
+            "Organism (ID)" | "Organism ID" => Column::OrganismIdentifier,
+            "Protein existence" => Column::ProteinExistence,
+            "Sequence version" => Column::SequenceVersion,
+            "Sequence" => Column::Sequence,
+            _ => Column::Extra,
+        }
+    }
+}
+
+/// Reader of UniProt tabular exports (`.tsv`), with a header line of column names
+///
+/// ```rust
+/// let tsv: &[u8] = b"Entry\tEntry Name\tReviewed\tProtein names\tGene Names\tOrganism\tOrganism (ID)\tProtein existence\tSequence version\tLength
+/// P02668\tCASK_BOVIN\treviewed\tKappa-casein [Cleaved into: Casoxin-C; Casoxin 6]\tCSN3 CSN10 CSNK\tBos taurus (Bovine)\t9913\tEvidence at protein level\t1\t190
+/// ";
+///
+/// let row = uniprot_fasta_header::TsvReader::new(tsv).unwrap().next().unwrap().unwrap();
+///
+/// assert_eq!(row.extra("Length"), Some("190"));
+/// assert_eq!(
+///     row.to_uniprotkb().unwrap().to_string(),
+///     ">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct TsvReader<R> {
+    reader: R,
+    names: Vec<String>,
+    columns: Vec<Column>,
+    line: Vec<u8>,
+    line_number: usize,
+}
+
+impl<R: BufRead> TsvReader<R> {
+    /// New reader, the first line gives the column names and must have an `Entry` column
+    pub fn new(mut reader: R) -> Result<Self, UniProtHeaderError> {
+        let mut line = Vec::new();
+        reader.read_until(b'\n', &mut line)?;
+        let names: Vec<String> = String::from_utf8_lossy(trim_newline(&line))
+            .split('\t')
+            .map(String::from)
+            .collect();
+        let columns: Vec<Column> = names.iter().map(|name| Column::from_name(name)).collect();
+        if !columns.contains(&Column::Accession) {
+            return Err(UniProtHeaderError::ParsingError(
+                names.join("\t"),
+                "missing column Entry".to_string(),
+            )
+            .at_line(1));
+        }
+        Ok(Self {
+            reader,
+            names,
+            columns,
+            line,
+            line_number: 1,
+        })
+    }
+
+    /// Column names
+    pub fn columns(&self) -> &[String] {
+        &self.names
+    }
+
+    fn parse_row(&self, line: &str) -> Result<TsvRow, UniProtHeaderError> {
+        let values: Vec<&str> = line.split('\t').collect();
+        if values.len() != self.columns.len() {
+            return Err(UniProtHeaderError::ParsingError(
+                line.to_string(),
+                format!("expected {} columns", self.columns.len()),
+            ));
+        }
+
+        let mut row = TsvRow::default();
+        let mut gene_names = None;
+        for ((column, name), value) in self.columns.iter().zip(&self.names).zip(values) {
+            let value = value.trim();
+            if value.is_empty() {
+                continue;
+            }
+            match column {
+                Column::Accession => row.accession = value.to_string(),
+                Column::EntryName => row.entry_name = Some(value.to_string()),
+                Column::Reviewed => {
+                    row.database = match value {
+                        "reviewed" => Some(Database::SwissProt),
+                        "unreviewed" => Some(Database::TrEMBL),
+                        _ => None,
+                    }
+                }
+                Column::ProteinNames => row.protein_name = Some(recommended_name(value)),
+                Column::GeneNames => gene_names = first_gene_name(value),
+                Column::PrimaryGeneName => {
+                    row.gene_name = value.split(';').next().map(|g| g.trim().to_string())
+                }
+                Column::Organism => row.organism_name = Some(scientific_name(value).to_string()),
+                Column::OrganismIdentifier => row.organism_identifier = Some(value.to_string()),
+                Column::ProteinExistence => {
+                    row.protein_existence = ProteinExistence::from_evidence(value)
+                }
+                Column::SequenceVersion => row.sequence_version = Some(value.to_string()),
+                Column::Sequence => row.sequence = Some(value.to_string()),
+                Column::Extra => row.extras.push((name.clone(), value.to_string())),
+            }
+        }
+        if row.gene_name.is_none() {
+            row.gene_name = gene_names.map(String::from);
+        }
+        if row.accession.is_empty() {
+            return Err(UniProtHeaderError::ParsingError(
+                line.to_string(),
+                "missing accession".to_string(),
+            ));
+        }
+        Ok(row)
+    }
+}
+
+impl<R: BufRead> Iterator for TsvReader<R> {
+    type Item = Result<TsvRow, UniProtHeaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.reader.read_until(b'\n', &mut self.line) {
+                Err(e) => return Some(Err(e.into())),
+                Ok(0) => return None,
+                Ok(_) => self.line_number += 1,
+            }
+            let line = String::from_utf8_lossy(trim_newline(&self.line)).to_string();
+            if line.is_empty() {
+                continue;
+            }
+            return Some(
+                self.parse_row(&line)
+                    .map_err(|e| e.at_line(self.line_number)),
+            );
+        }
+    }
+}
+
+/// Rows of a UniProt tabular export indexed by accession, to join them with parsed FASTA headers
+///
+/// ```rust
+/// use uniprot_fasta_header::TsvTable;
+///
+/// let tsv: &[u8] = b"Entry\tMass\nP02668\t21269\n";
+/// let table = TsvTable::read(tsv).unwrap();
+///
+/// let header = uniprot_fasta_header::uniprotkb(b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1").unwrap();
+/// assert_eq!(table.for_header(&header).unwrap().extra("Mass"), Some("21269"));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TsvTable {
+    columns: Vec<String>,
+    rows: Vec<TsvRow>,
+    index: HashMap<String, usize>,
+}
+
+impl TsvTable {
+    /// Read a tabular export, fails on accessions found twice
+    pub fn read<R: BufRead>(reader: R) -> Result<Self, UniProtHeaderError> {
+        let reader = TsvReader::new(reader)?;
+        let mut table = TsvTable {
+            columns: reader.columns().to_vec(),
+            ..TsvTable::default()
+        };
+        for row in reader {
+            let row = row?;
+            if table.index.contains_key(&row.accession) {
+                return Err(UniProtHeaderError::DuplicateHeader(row.accession));
+            }
+            table.index.insert(row.accession.clone(), table.rows.len());
+            table.rows.push(row);
+        }
+        Ok(table)
+    }
+
+    /// Column names
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Row of an accession
+    pub fn get(&self, accession: &str) -> Option<&TsvRow> {
+        self.index.get(accession).map(|&i| &self.rows[i])
+    }
+
+    /// Row of a parsed header, isoforms fall back to their canonical accession
+    pub fn for_header<H: FastaHeader + ?Sized>(&self, header: &H) -> Option<&TsvRow> {
+        let identifier = header.identifier();
+        self.get(&identifier).or_else(|| {
+            identifier
+                .split_once('-')
+                .and_then(|(canonical, _)| self.get(canonical))
+        })
+    }
+
+    /// Iterate over the rows in file order
+    pub fn iter(&self) -> impl Iterator<Item = &TsvRow> {
+        self.rows.iter()
+    }
+
+    /// Number of rows
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// No rows
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uniprotkb, uniprotkb_iso};
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    const TSV: &str = "Entry\tEntry Name\tReviewed\tProtein names\tGene Names\tGene Names (primary)\tOrganism\tOrganism (ID)\tProtein existence\tSequence version\tSequence\tSubcellular location [CC]
+P31946\t1433B_HUMAN\treviewed\t14-3-3 protein beta/alpha (Protein 1054) (Protein kinase C inhibitor protein 1) (KCIP-1) [Cleaved into: 14-3-3 protein beta/alpha, N-terminally processed]\tYWHAB\tYWHAB\tHomo sapiens (Human)\t9606\tEvidence at protein level\t3\tMTMDK\tCytoplasm
+A0A023GPI8\tA0A023GPI8_CANAL\tunreviewed\tLectin alpha chain (Fragment)\tCLP_0001 ORF1\t\tCanavalia lineata (Beach bean) (Dolichos lineatus)\t28957\tPredicted\t1\tADTIV\t
+";
+
+    #[test]
+    fn rows() {
+        let reader = TsvReader::new(TSV.as_bytes()).unwrap();
+        assert_eq!(reader.columns().len(), 12);
+        let rows: Vec<TsvRow> = reader.map(Result::unwrap).collect();
+        assert_eq!(
+            rows[0],
+            TsvRow {
+                accession: "P31946".to_string(),
+                entry_name: Some("1433B_HUMAN".to_string()),
+                database: Some(Database::SwissProt),
+                protein_name: Some("14-3-3 protein beta/alpha".to_string()),
+                gene_name: Some("YWHAB".to_string()),
+                organism_name: Some("Homo sapiens".to_string()),
+                organism_identifier: Some("9606".to_string()),
+                protein_existence: Some(ProteinExistence::ExperimentalEvidenceProtein),
+                sequence_version: Some("3".to_string()),
+                sequence: Some("MTMDK".to_string()),
+                extras: vec![(
+                    "Subcellular location [CC]".to_string(),
+                    "Cytoplasm".to_string()
+                )],
+            }
+        );
+        assert_eq!(
+            rows[1].to_record().unwrap(),
+            Record {
+                header: ">tr|A0A023GPI8|A0A023GPI8_CANAL Lectin alpha chain (Fragment) OS=Canavalia lineata OX=28957 GN=CLP_0001 PE=4 SV=1".to_string(),
+                sequence: "ADTIV".to_string(),
+            }
+        );
+    }
+
+    #[test_case("Kappa-casein", "Kappa-casein" ; "single name")]
+    #[test_case("Aspartate aminotransferase, mitochondrial (mAspAT) (EC 2.6.1.1)", "Aspartate aminotransferase, mitochondrial" ; "alternative names")]
+    #[test_case("Lectin (Fragments) (LEC)", "Lectin (Fragments)" ; "fragments")]
+    #[test_case("tRNA (guanine-N(7)-)-methyltransferase", "tRNA (guanine-N(7)-)-methyltransferase" ; "parentheses inside the name")]
+    fn protein_names(names: &str, expected: &str) {
+        pretty_assertions::assert_eq!(recommended_name(names), expected);
+    }
+
+    #[test_case("CSN3 CSN10 CSNK", Some("CSN3") ; "synonyms")]
+    #[test_case("GENE1; GENE2", Some("GENE1") ; "several genes")]
+    #[test_case("GENE1 SYN1; GENE2", Some("GENE1") ; "synonyms of several genes")]
+    #[test_case(";", None ; "no gene")]
+    fn gene_names(names: &str, expected: Option<&str>) {
+        pretty_assertions::assert_eq!(first_gene_name(names), expected);
+    }
+
+    #[test]
+    fn join() {
+        let table = TsvTable::read(TSV.as_bytes()).unwrap();
+        assert_eq!(table.len(), 2);
+
+        let header = uniprotkb(b">sp|P31946|1433B_HUMAN 14-3-3 protein beta/alpha OS=Homo sapiens OX=9606 GN=YWHAB PE=1 SV=3").unwrap();
+        let row = table.for_header(&header).unwrap();
+        assert_eq!(row.to_uniprotkb().unwrap(), header);
+
+        let isoform = uniprotkb_iso(b">sp|P31946-2|1433B_HUMAN Isoform Short of 14-3-3 protein beta/alpha OS=Homo sapiens OX=9606 GN=YWHAB").unwrap();
+        assert_eq!(table.for_header(&isoform), Some(row));
+    }
+
+    #[test]
+    fn missing_columns() {
+        let row = TsvReader::new(&b"Entry\tLength\nP02668\t190\n"[..])
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            row.to_uniprotkb().unwrap_err(),
+            UniProtHeaderError::ParsingError(
+                "P02668".to_string(),
+                "missing column Reviewed".to_string()
+            )
+        );
+        assert!(TsvReader::new(&b"Entry Name\tLength\n"[..]).is_err());
+        assert!(matches!(
+            TsvReader::new(&b"Entry\tLength\nP02668\n"[..])
+                .unwrap()
+                .next()
+                .unwrap(),
+            Err(UniProtHeaderError::Line(2, _))
+        ));
+    }
+}
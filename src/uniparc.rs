@@ -0,0 +1,124 @@
+use crate::{
+    error::UniProtHeaderError,
+    filter::{Field, Filterable},
+    header::FastaHeader,
+    parser::{chevron, space, uniparc_id, uniparc_status},
+};
+use nom::{
+    combinator::{all_consuming, opt},
+    error::ParseError,
+    sequence::preceded,
+    IResult,
+};
+use std::{borrow::Cow, fmt};
+
+/// UniParc header
+///
+/// `>UniqueIdentifier status=Status`
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UniParc {
+    /// UniParc identifier (`UPI0000000001`)
+    pub identifier: String,
+    /// Whether the sequence is still in at least one source database, `None` if the status is missing
+    pub active: Option<bool>,
+}
+
+/// Write the UniParc fasta header
+impl fmt::Display for UniParc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, ">{}", self.identifier)?;
+        match self.active {
+            Some(true) => write!(f, " status=active"),
+            Some(false) => write!(f, " status=inactive"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Filterable for UniParc {
+    fn field(&self, field: Field) -> Option<Cow<'_, str>> {
+        match field {
+            Field::Database => Some(Cow::Borrowed("uniparc")),
+            Field::Accession => Some(Cow::Borrowed(&self.identifier)),
+            _ => None,
+        }
+    }
+}
+
+impl FastaHeader for UniParc {
+    fn identifier(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.identifier)
+    }
+
+    fn organism_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn taxon_id(&self) -> Option<&str> {
+        None
+    }
+
+    fn gene_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn description(&self) -> &str {
+        ""
+    }
+
+    fn source_database(&self) -> &'static str {
+        "uniparc"
+    }
+}
+
+/// Parse a UniParc fasta header
+pub fn uniparc(string: &[u8]) -> Result<UniParc, UniProtHeaderError> {
+    match parse_uniparc(string) {
+        Ok((_, parsed)) => Ok(parsed),
+        Err(err) => match err {
+            nom::Err::Incomplete(_i) => Err(UniProtHeaderError::Incomplete),
+            nom::Err::Error((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+            nom::Err::Failure((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+        },
+    }
+}
+
+fn parse_uniparc(input: &[u8]) -> IResult<&[u8], UniParc> {
+    let (input, _) = chevron(input)?;
+    let (input, identifier) = uniparc_id(input)?;
+    let (input, active) = all_consuming(opt(preceded(space, uniparc_status)))(input)?;
+
+    Ok((
+        input,
+        UniParc {
+            identifier: String::from_utf8_lossy(identifier).to_string(),
+            active,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use test_case::test_case;
+
+    #[test_case(">UPI0000000001 status=active", Some(true) ; "active")]
+    #[test_case(">UPI00000000A1 status=inactive", Some(false) ; "inactive")]
+    #[test_case(">UPI0000000001", None ; "without status")]
+    fn test_uniparc(header: &str, active: Option<bool>) {
+        let parsed = uniparc(header.as_bytes()).unwrap();
+        pretty_assertions::assert_eq!(parsed.active, active);
+        pretty_assertions::assert_eq!(parsed.to_string(), header);
+    }
+
+    #[test]
+    fn test_uniparc_invalid() {
+        assert!(uniparc(b">UPI0000000001 status=unknown").is_err());
+        assert_eq!(
+            uniparc(b">UPI000000000G").unwrap_err(),
+            UniProtHeaderError::ParsingError("000000000G".to_string(), "TakeWhileMN".to_string())
+        );
+    }
+}
@@ -1,15 +1,16 @@
 use crate::{
     error::UniProtHeaderError,
     parser::{
-        chevron, db, entry_name, evidence, optional_gene_name, organism_id, os_until_ox, pipe,
-        space, unique_id, until_os, version,
+        chevron, db, entry_name, evidence, optional_gene_name, organism, pipe, space, unique_id,
+        until_os, version,
     },
-    Database, ProteinExistence,
+    Database, HeaderLayout, ProteinExistence,
 };
 use nom::{error::ParseError, IResult};
+use std::fmt;
 
 /// UniProtKB header
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UniProtKB {
     /// UniProtKB database
@@ -23,7 +24,7 @@ pub struct UniProtKB {
     /// Organism name
     pub organism_name: String,
     /// NCBI iaxonomic identifier (https://www.uniprot.org/help/taxonomic%5Fidentifier)
-    pub organism_identifier: String,
+    pub organism_identifier: Option<String>,
     /// Gene name (https://www.uniprot.org/help/gene%5Fname)
     pub gene_name: Option<String>,
     /// Protein existence (https://www.uniprot.org/help/protein%5Fexistence)
@@ -40,7 +41,7 @@ impl Default for UniProtKB {
             entry_name: String::default(),
             protein_name: String::default(),
             organism_name: String::default(),
-            organism_identifier: String::default(),
+            organism_identifier: None,
             gene_name: None,
             protein_existence: ProteinExistence::Uncertain,
             sequence_version: String::default(),
@@ -48,9 +49,44 @@ impl Default for UniProtKB {
     }
 }
 
-/// Parse a UniProtKB fasta header
+/// Write the UniProtKB fasta header
+impl fmt::Display for UniProtKB {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ">{}|{}|{} {} OS={}",
+            self.database.code(),
+            self.identifier,
+            self.entry_name,
+            self.protein_name,
+            self.organism_name,
+        )?;
+        if let Some(organism_identifier) = &self.organism_identifier {
+            write!(f, " OX={}", organism_identifier)?;
+        }
+        if let Some(gene_name) = &self.gene_name {
+            write!(f, " GN={}", gene_name)?;
+        }
+        write!(
+            f,
+            " PE={} SV={}",
+            self.protein_existence.level(),
+            self.sequence_version
+        )
+    }
+}
+
+/// Parse a UniProtKB fasta header, current or legacy
 pub fn uniprotkb(string: &[u8]) -> Result<UniProtKB, UniProtHeaderError> {
-    match parse_uniprotkb(string) {
+    uniprotkb_with_layout(string, HeaderLayout::Auto)
+}
+
+/// Parse a UniProtKB fasta header with the given layout
+pub fn uniprotkb_with_layout(
+    string: &[u8],
+    layout: HeaderLayout,
+) -> Result<UniProtKB, UniProtHeaderError> {
+    match parse_uniprotkb(string, layout.resolve(string)) {
         Ok((_, parsed)) => Ok(parsed),
         Err(err) => match err {
             nom::Err::Incomplete(_i) => Err(UniProtHeaderError::Incomplete),
@@ -60,7 +96,7 @@ pub fn uniprotkb(string: &[u8]) -> Result<UniProtKB, UniProtHeaderError> {
     }
 }
 
-fn parse_uniprotkb(input: &[u8]) -> IResult<&[u8], UniProtKB> {
+fn parse_uniprotkb(input: &[u8], layout: HeaderLayout) -> IResult<&[u8], UniProtKB> {
     let (input, _) = chevron(input)?;
     let (input, database) = db(input)?;
     let (input, _) = pipe(input)?;
@@ -70,19 +106,14 @@ fn parse_uniprotkb(input: &[u8]) -> IResult<&[u8], UniProtKB> {
     let (input, _) = space(input)?;
     let (input, protein) = until_os(input)?;
     let (input, _) = space(input)?;
-    let (input, organism) = os_until_ox(input)?;
-    let (input, _) = space(input)?;
-    let (input, organism_id) = organism_id(input)?;
+    let (input, (organism, organism_id)) = organism(layout)(input)?;
     let (input, _) = space(input)?;
     let (input, (gene, _)) = optional_gene_name(input)?; // + optional space
     let (input, evidence) = evidence(input)?;
     let (input, _) = space(input)?;
     let (input, version) = version(input)?;
 
-    let gene_name = match gene {
-        Some(g) => Some(String::from_utf8_lossy(g).to_string()),
-        None => None,
-    };
+    let gene_name = gene.map(|g| String::from_utf8_lossy(g).to_string());
 
     Ok((
         input,
@@ -92,7 +123,8 @@ fn parse_uniprotkb(input: &[u8]) -> IResult<&[u8], UniProtKB> {
             entry_name: String::from_utf8(entry).unwrap(),
             protein_name: String::from_utf8_lossy(protein).trim().to_string(),
             organism_name: String::from_utf8_lossy(organism).trim().to_string(),
-            organism_identifier: String::from_utf8_lossy(organism_id).trim().to_string(),
+            organism_identifier: organism_id
+                .map(|ox| String::from_utf8_lossy(ox).trim().to_string()),
             gene_name,
             protein_existence: evidence,
             sequence_version: String::from_utf8_lossy(version).trim().to_string(),
@@ -113,7 +145,7 @@ mod tests {
             entry_name: "ACN2_ACAGO".to_string(),
             protein_name: "Acanthoscurrin-2 (Fragment)".to_string(),
             organism_name: "Acanthoscurria gomesiana".to_string(),
-            organism_identifier: "115339".to_string(),
+            organism_identifier: Some("115339".to_string()),
             gene_name: Some("acantho2".to_string()),
             protein_existence: ProteinExistence::ExperimentalEvidenceProtein,
             sequence_version: "1".to_string(),
@@ -131,7 +163,7 @@ mod tests {
             protein_name: "Acetoin catabolism protein X".to_string(),
             organism_name: "Cupriavidus necator (strain ATCC 17699 / H16 / DSM 428 / Stanier 337)"
                 .to_string(),
-            organism_identifier: "381666".to_string(),
+            organism_identifier: Some("381666".to_string()),
             gene_name: Some("acoX".to_string()),
             protein_existence: ProteinExistence::Predicted,
             sequence_version: "2".to_string(),
@@ -148,7 +180,7 @@ mod tests {
             entry_name: "HA22_MOUSE".to_string(),
             protein_name: "H-2 class II histocompatibility antigen, E-K alpha chain".to_string(),
             organism_name: "Mus musculus".to_string(),
-            organism_identifier: "10090".to_string(),
+            organism_identifier: Some("10090".to_string()),
             gene_name: None,
             protein_existence: ProteinExistence::ExperimentalEvidenceProtein,
             sequence_version: "1".to_string(),
@@ -165,7 +197,7 @@ mod tests {
             entry_name: "Q3SA23_9HIV1".to_string(),
             protein_name: "Protein Nef (Fragment)".to_string(),
             organism_name: "Human immunodeficiency virus 1".to_string(),
-            organism_identifier: "11676".to_string(),
+            organism_identifier: Some("11676".to_string()),
             gene_name: Some("nef".to_string()),
             protein_existence: ProteinExistence::InferredHomology,
             sequence_version: "1".to_string(),
@@ -182,7 +214,7 @@ mod tests {
             entry_name: "CASK_BOVIN".to_string(),
             protein_name: "Kappa-casein".to_string(),
             organism_name: "Bos taurus".to_string(),
-            organism_identifier: "9913".to_string(),
+            organism_identifier: Some("9913".to_string()),
             gene_name: Some("CSN3".to_string()),
             protein_existence: ProteinExistence::ExperimentalEvidenceProtein,
             sequence_version: "1".to_string(),
@@ -192,6 +224,47 @@ mod tests {
         assert_eq!(uniprotkb(test_header).unwrap(), entry)
     }
 
+    #[test]
+    fn test_uniprot_display() {
+        let header = ">sp|P27748|ACOX_CUPNH Acetoin catabolism protein X OS=Cupriavidus necator (strain ATCC 17699 / H16 / DSM 428 / Stanier 337) OX=381666 GN=acoX PE=4 SV=2";
+        assert_eq!(uniprotkb(header.as_bytes()).unwrap().to_string(), header);
+
+        let header = ">sp|P04224|HA22_MOUSE H-2 class II histocompatibility antigen, E-K alpha chain OS=Mus musculus OX=10090 PE=1 SV=1";
+        assert_eq!(uniprotkb(header.as_bytes()).unwrap().to_string(), header);
+    }
+
+    #[test]
+    fn test_uniprot_aatm_rabit_legacy() {
+        let entry = UniProtKB {
+            database: Database::SwissProt,
+            identifier: "P12345".to_string(),
+            entry_name: "AATM_RABIT".to_string(),
+            protein_name: "Aspartate aminotransferase, mitochondrial".to_string(),
+            organism_name: "Oryctolagus cuniculus".to_string(),
+            organism_identifier: None,
+            gene_name: Some("GOT2".to_string()),
+            protein_existence: ProteinExistence::ExperimentalEvidenceProtein,
+            sequence_version: "2".to_string(),
+        };
+        let test_header = ">sp|P12345|AATM_RABIT Aspartate aminotransferase, mitochondrial OS=Oryctolagus cuniculus GN=GOT2 PE=1 SV=2".as_bytes();
+        assert_eq!(uniprotkb(test_header).unwrap(), entry);
+        assert_eq!(
+            uniprotkb_with_layout(test_header, HeaderLayout::Legacy).unwrap(),
+            entry
+        );
+        assert!(uniprotkb_with_layout(test_header, HeaderLayout::Current).is_err());
+        assert_eq!(entry.to_string().as_bytes(), test_header);
+    }
+
+    #[test]
+    fn test_uniprot_legacy_no_gene_name() {
+        let test_header = ">sp|P04224|HA22_MOUSE H-2 class II histocompatibility antigen, E-K alpha chain OS=Mus musculus PE=1 SV=1".as_bytes();
+        let parsed = uniprotkb(test_header).unwrap();
+        assert_eq!(parsed.organism_name, "Mus musculus");
+        assert_eq!(parsed.gene_name, None);
+        assert_eq!(parsed.organism_identifier, None);
+    }
+
     #[test]
     fn test_uniprot_ypfu_ecoli() {
         let entry = UniProtKB {
@@ -200,7 +273,7 @@ mod tests {
             entry_name: "YPFU_ECOLI".to_string(),
             protein_name: "Uncharacterized protein in traD-traI intergenic region".to_string(),
             organism_name: "Escherichia coli (strain K12)".to_string(),
-            organism_identifier: "83333".to_string(),
+            organism_identifier: Some("83333".to_string()),
             gene_name: None,
             protein_existence: ProteinExistence::InferredHomology,
             sequence_version: "1".to_string(),
@@ -1,15 +1,15 @@
 use crate::{
     error::UniProtHeaderError,
     parser::{
-        chevron, db, entry_name, iso_id, optional_gene_name, organism_id, os_until_ox, pipe, space,
-        until_os,
+        chevron, db, entry_name, iso_id, optional_gene_name, organism, pipe, space, until_os,
     },
-    Database,
+    Database, HeaderLayout,
 };
-use nom::{error::ParseError, IResult};
+use nom::{combinator::opt, error::ParseError, IResult};
+use std::fmt;
 
 /// UniProtKB isoform header
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UniProtKBIsoform {
     /// UniProtKB database
@@ -25,7 +25,7 @@ pub struct UniProtKBIsoform {
     /// Organism name
     pub organism_name: String,
     /// NCBI iaxonomic identifier (https://www.uniprot.org/help/taxonomic%5Fidentifier)
-    pub organism_identifier: String,
+    pub organism_identifier: Option<String>,
     /// Gene name (https://www.uniprot.org/help/gene%5Fname)
     pub gene_name: Option<String>,
 }
@@ -39,15 +39,46 @@ impl Default for UniProtKBIsoform {
             entry_name: String::default(),
             protein_name: String::default(),
             organism_name: String::default(),
-            organism_identifier: String::default(),
+            organism_identifier: None,
             gene_name: None,
         }
     }
 }
 
-/// Parse a UniProtKB isoform fasta header
+/// Write the UniProtKB isoform fasta header
+impl fmt::Display for UniProtKBIsoform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ">{}|{}-{}|{} {} OS={}",
+            self.database.code(),
+            self.identifier,
+            self.isoform,
+            self.entry_name,
+            self.protein_name,
+            self.organism_name,
+        )?;
+        if let Some(organism_identifier) = &self.organism_identifier {
+            write!(f, " OX={}", organism_identifier)?;
+        }
+        if let Some(gene_name) = &self.gene_name {
+            write!(f, " GN={}", gene_name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a UniProtKB isoform fasta header, current or legacy
 pub fn uniprotkb_iso(string: &[u8]) -> Result<UniProtKBIsoform, UniProtHeaderError> {
-    match parse_uniprotkb_iso(string) {
+    uniprotkb_iso_with_layout(string, HeaderLayout::Auto)
+}
+
+/// Parse a UniProtKB isoform fasta header with the given layout
+pub fn uniprotkb_iso_with_layout(
+    string: &[u8],
+    layout: HeaderLayout,
+) -> Result<UniProtKBIsoform, UniProtHeaderError> {
+    match parse_uniprotkb_iso(string, layout.resolve(string)) {
         Ok((_, parsed)) => Ok(parsed),
         Err(err) => match err {
             nom::Err::Incomplete(_i) => Err(UniProtHeaderError::Incomplete),
@@ -57,7 +88,7 @@ pub fn uniprotkb_iso(string: &[u8]) -> Result<UniProtKBIsoform, UniProtHeaderErr
     }
 }
 
-fn parse_uniprotkb_iso(input: &[u8]) -> IResult<&[u8], UniProtKBIsoform> {
+fn parse_uniprotkb_iso(input: &[u8], layout: HeaderLayout) -> IResult<&[u8], UniProtKBIsoform> {
     let (input, _) = chevron(input)?;
     let (input, database) = db(input)?;
     let (input, _) = pipe(input)?;
@@ -67,16 +98,11 @@ fn parse_uniprotkb_iso(input: &[u8]) -> IResult<&[u8], UniProtKBIsoform> {
     let (input, _) = space(input)?;
     let (input, protein) = until_os(input)?;
     let (input, _) = space(input)?;
-    let (input, organism) = os_until_ox(input)?;
-    let (input, _) = space(input)?;
-    let (input, organism_id) = organism_id(input)?;
-    let (input, _) = space(input)?;
+    let (input, (organism, organism_id)) = organism(layout)(input)?;
+    let (input, _) = opt(space)(input)?;
     let (input, (gene, _)) = optional_gene_name(input)?; // + optional space
 
-    let gene_name = match gene {
-        Some(g) => Some(String::from_utf8_lossy(g).to_string()),
-        None => None,
-    };
+    let gene_name = gene.map(|g| String::from_utf8_lossy(g).to_string());
 
     Ok((
         input,
@@ -87,7 +113,8 @@ fn parse_uniprotkb_iso(input: &[u8]) -> IResult<&[u8], UniProtKBIsoform> {
             entry_name: String::from_utf8(entry).unwrap(),
             protein_name: String::from_utf8_lossy(protein).trim().to_string(),
             organism_name: String::from_utf8_lossy(organism).trim().to_string(),
-            organism_identifier: String::from_utf8_lossy(organism_id).trim().to_string(),
+            organism_identifier: organism_id
+                .map(|ox| String::from_utf8_lossy(ox).trim().to_string()),
             gene_name,
         },
     ))
@@ -107,7 +134,7 @@ mod tests {
             entry_name: "1433B_MACFA".to_string(),
             protein_name: "Isoform Short of 14-3-3 protein beta/alpha".to_string(),
             organism_name: "Macaca fascicularis".to_string(),
-            organism_identifier: "9541".to_string(),
+            organism_identifier: Some("9541".to_string()),
             gene_name: Some("YWHAB".to_string()),
         };
         let test_header =
@@ -124,7 +151,7 @@ mod tests {
             entry_name: "ALG2_HUMAN".to_string(),
             protein_name: "Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2".to_string(),
             organism_name: "Homo sapiens".to_string(),
-            organism_identifier: "9606".to_string(),
+            organism_identifier: Some("9606".to_string()),
             gene_name: Some("ALG2".to_string()),
         };
         let test_header =
@@ -141,7 +168,7 @@ mod tests {
             entry_name: "AGL27_ARATH".to_string(),
             protein_name: "Isoform 4 of Agamous-like MADS-box protein AGL27".to_string(),
             organism_name: "Arabidopsis thaliana".to_string(),
-            organism_identifier: "3702".to_string(),
+            organism_identifier: Some("3702".to_string()),
             gene_name: Some("AGL27".to_string()),
         };
         let test_header =
@@ -149,6 +176,25 @@ mod tests {
         assert_eq!(uniprotkb_iso(test_header).unwrap(), entry)
     }
 
+    #[test]
+    fn test_uniprot_iso_display() {
+        let header = ">sp|Q9H553-2|ALG2_HUMAN Isoform 2 of Alpha-1,3/1,6-mannosyltransferase ALG2 OS=Homo sapiens OX=9606 GN=ALG2";
+        assert_eq!(
+            uniprotkb_iso(header.as_bytes()).unwrap().to_string(),
+            header
+        );
+    }
+
+    #[test]
+    fn test_uniprot_1433b_macfa_iso_legacy() {
+        let test_header = ">sp|Q4R572-2|1433B_MACFA Isoform Short of 14-3-3 protein beta/alpha OS=Macaca fascicularis GN=YWHAB".as_bytes();
+        let parsed = uniprotkb_iso(test_header).unwrap();
+        assert_eq!(parsed.organism_name, "Macaca fascicularis");
+        assert_eq!(parsed.organism_identifier, None);
+        assert_eq!(parsed.gene_name, Some("YWHAB".to_string()));
+        assert_eq!(parsed.to_string().as_bytes(), test_header);
+    }
+
     #[test]
     fn test_uniprot_ters_bpspp_iso() {
         let entry = UniProtKBIsoform {
@@ -158,7 +204,7 @@ mod tests {
             entry_name: "TERS_BPSPP".to_string(),
             protein_name: "Isoform G1P* of Terminase small subunit".to_string(),
             organism_name: "Bacillus phage SPP1".to_string(),
-            organism_identifier: "10724".to_string(),
+            organism_identifier: Some("10724".to_string()),
             gene_name: Some("1".to_string()),
         };
         let test_header =
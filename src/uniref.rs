@@ -0,0 +1,186 @@
+use crate::{
+    error::UniProtHeaderError,
+    filter::{Field, Filterable},
+    header::FastaHeader,
+    parser::{chevron, members, rep_id, space, uniref_id, uniref_taxon, until_members},
+};
+use nom::{combinator::all_consuming, error::ParseError, sequence::preceded, IResult};
+use std::{borrow::Cow, fmt};
+
+/// UniRef cluster header
+///
+/// `>UniRefIdentity_RepresentativeMember ClusterName n=Members Tax=Taxon TaxID=TaxonIdentifier RepID=RepresentativeEntryName`
+#[derive(Debug, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct UniRef {
+    /// Sequence identity threshold of the cluster: 100, 90 or 50
+    pub identity: u8,
+    /// Accession of the representative member, UniProtKB or UniParc (`Q9H553`)
+    pub identifier: String,
+    /// Cluster name
+    pub cluster_name: String,
+    /// Number of members
+    pub members: usize,
+    /// Lowest common taxon of the members
+    pub taxon: String,
+    /// NCBI taxonomic identifier of the common taxon
+    pub taxon_id: Option<String>,
+    /// Entry name of the representative member (`ALG2_HUMAN`)
+    pub representative: String,
+}
+
+impl UniRef {
+    /// Cluster identifier (`UniRef90_Q9H553`)
+    pub fn cluster_id(&self) -> String {
+        format!("UniRef{}_{}", self.identity, self.identifier)
+    }
+}
+
+/// Write the UniRef fasta header
+impl fmt::Display for UniRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            ">{} {} n={} Tax={}",
+            self.cluster_id(),
+            self.cluster_name,
+            self.members,
+            self.taxon
+        )?;
+        if let Some(taxon_id) = &self.taxon_id {
+            write!(f, " TaxID={}", taxon_id)?;
+        }
+        write!(f, " RepID={}", self.representative)
+    }
+}
+
+impl Filterable for UniRef {
+    fn field(&self, field: Field) -> Option<Cow<'_, str>> {
+        match field {
+            Field::Database => Some(Cow::Borrowed("uniref")),
+            Field::Accession => Some(Cow::Borrowed(&self.identifier)),
+            Field::EntryName => Some(Cow::Borrowed(&self.representative)),
+            Field::ProteinName => Some(Cow::Borrowed(&self.cluster_name)),
+            Field::OrganismName => Some(Cow::Borrowed(&self.taxon)),
+            Field::OrganismIdentifier => self.taxon_id.as_deref().map(Cow::Borrowed),
+            _ => None,
+        }
+    }
+}
+
+impl FastaHeader for UniRef {
+    fn identifier(&self) -> Cow<'_, str> {
+        Cow::Owned(self.cluster_id())
+    }
+
+    fn organism_name(&self) -> Option<&str> {
+        Some(&self.taxon)
+    }
+
+    fn taxon_id(&self) -> Option<&str> {
+        self.taxon_id.as_deref()
+    }
+
+    fn gene_name(&self) -> Option<&str> {
+        None
+    }
+
+    fn description(&self) -> &str {
+        &self.cluster_name
+    }
+
+    fn source_database(&self) -> &'static str {
+        "uniref"
+    }
+}
+
+/// Parse a UniRef fasta header
+///
+/// ```rust
+/// let header = b">UniRef90_Q9H553 Alpha-1,3/1,6-mannosyltransferase ALG2 n=2 Tax=Homo sapiens TaxID=9606 RepID=ALG2_HUMAN";
+///
+/// let parsed = uniprot_fasta_header::uniref(header).unwrap();
+///
+/// assert_eq!(parsed.identity, 90);
+/// assert_eq!(parsed.members, 2);
+/// assert_eq!(parsed.taxon_id, Some("9606".to_string()));
+/// ```
+pub fn uniref(string: &[u8]) -> Result<UniRef, UniProtHeaderError> {
+    match parse_uniref(string) {
+        Ok((_, parsed)) => Ok(parsed),
+        Err(err) => match err {
+            nom::Err::Incomplete(_i) => Err(UniProtHeaderError::Incomplete),
+            nom::Err::Error((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+            nom::Err::Failure((rest, kind)) => Err(UniProtHeaderError::from_error_kind(rest, kind)),
+        },
+    }
+}
+
+fn parse_uniref(input: &[u8]) -> IResult<&[u8], UniRef> {
+    let (input, _) = chevron(input)?;
+    let (input, (identity, identifier)) = uniref_id(input)?;
+    let (input, cluster_name) = preceded(space, until_members)(input)?;
+    let (input, members) = preceded(space, members)(input)?;
+    let (input, (taxon, taxon_id)) = preceded(space, uniref_taxon)(input)?;
+    let (input, representative) = all_consuming(preceded(space, rep_id))(input)?;
+
+    Ok((
+        input,
+        UniRef {
+            identity,
+            identifier: String::from_utf8_lossy(identifier).to_string(),
+            cluster_name: String::from_utf8_lossy(cluster_name).to_string(),
+            members,
+            taxon: String::from_utf8_lossy(taxon).to_string(),
+            taxon_id: taxon_id.map(|t| String::from_utf8_lossy(t).to_string()),
+            representative: String::from_utf8_lossy(representative).to_string(),
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_uniref90_q9h553() {
+        let entry = UniRef {
+            identity: 90,
+            identifier: "Q9H553".to_string(),
+            cluster_name: "Alpha-1,3/1,6-mannosyltransferase ALG2".to_string(),
+            members: 2,
+            taxon: "Homo sapiens".to_string(),
+            taxon_id: Some("9606".to_string()),
+            representative: "ALG2_HUMAN".to_string(),
+        };
+        let test_header = ">UniRef90_Q9H553 Alpha-1,3/1,6-mannosyltransferase ALG2 n=2 Tax=Homo sapiens TaxID=9606 RepID=ALG2_HUMAN";
+        let parsed = uniref(test_header.as_bytes()).unwrap();
+        assert_eq!(parsed, entry);
+        assert_eq!(parsed.to_string(), test_header);
+    }
+
+    #[test]
+    fn test_uniref100_uniparc_representative() {
+        let parsed = uniref(b">UniRef100_UPI0002A5E5E5 hypothetical protein n=1 Tax=Streptomyces sp. TaxID=1931 RepID=UPI0002A5E5E5").unwrap();
+        assert_eq!(parsed.identifier, "UPI0002A5E5E5");
+        assert_eq!(parsed.taxon, "Streptomyces sp.");
+    }
+
+    #[test]
+    fn test_uniref_without_taxid() {
+        let parsed = uniref(
+            b">UniRef50_Q9K794 Putative AgrB-like protein n=2 Tax=Bacillus RepID=AGRB_BACHD",
+        )
+        .unwrap();
+        assert_eq!(parsed.taxon_id, None);
+        assert_eq!(parsed.representative, "AGRB_BACHD");
+    }
+
+    #[test]
+    fn test_uniref_missing_members() {
+        assert!(
+            uniref(b">UniRef90_Q9H553 ALG2 Tax=Homo sapiens TaxID=9606 RepID=ALG2_HUMAN").is_err()
+        );
+    }
+}
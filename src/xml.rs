@@ -0,0 +1,431 @@
+use crate::{error::UniProtHeaderError, fasta::Record, Database, ProteinExistence, UniProtKB};
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use std::io::BufRead;
+
+/// UniProtKB header and sequence of an XML entry
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct XmlEntry {
+    /// Header fields of the entry
+    pub header: UniProtKB,
+    /// Sequence
+    pub sequence: String,
+}
+
+impl XmlEntry {
+    /// FASTA record of the entry
+    pub fn to_record(&self) -> Record {
+        Record {
+            header: self.header.to_string(),
+            sequence: self.sequence.clone(),
+        }
+    }
+}
+
+// Fields collected while reading an entry
+#[derive(Debug, Default)]
+struct EntryBuilder {
+    dataset: String,
+    accession: Option<String>,
+    entry_name: Option<String>,
+    protein_name: Option<String>,
+    organism_name: Option<String>,
+    organism_identifier: Option<String>,
+    // (type, name) of the first gene
+    gene_names: Vec<(String, String)>,
+    genes: usize,
+    protein_existence: Option<ProteinExistence>,
+    sequence_version: Option<String>,
+    fragment: Option<String>,
+    sequence: String,
+}
+
+impl EntryBuilder {
+    // Primary name of the first gene, or its first ordered locus or ORF name
+    fn gene_name(&self) -> Option<String> {
+        ["primary", "ordered locus", "ORF"].iter().find_map(|kind| {
+            self.gene_names
+                .iter()
+                .find(|(name_type, _)| name_type == kind)
+                .map(|(_, name)| name.clone())
+        })
+    }
+
+    fn build(mut self) -> Result<XmlEntry, UniProtHeaderError> {
+        let accession = self.accession.take().unwrap_or_default();
+        let error = |message: &str| {
+            UniProtHeaderError::ParsingError(accession.clone(), message.to_string())
+        };
+
+        let database = match self.dataset.as_str() {
+            "Swiss-Prot" => Database::SwissProt,
+            "TrEMBL" => Database::TrEMBL,
+            _ => return Err(error("unknown dataset")),
+        };
+        if accession.is_empty() {
+            return Err(error("missing accession"));
+        }
+        let mut protein_name = self
+            .protein_name
+            .take()
+            .ok_or_else(|| error("missing protein name"))?;
+        match self.fragment.as_deref() {
+            Some("single") => protein_name.push_str(" (Fragment)"),
+            Some("multiple") => protein_name.push_str(" (Fragments)"),
+            _ => (),
+        }
+        let gene_name = self.gene_name();
+
+        Ok(XmlEntry {
+            header: UniProtKB {
+                database,
+                identifier: accession.clone(),
+                entry_name: self.entry_name.ok_or_else(|| error("missing entry name"))?,
+                protein_name,
+                organism_name: self
+                    .organism_name
+                    .ok_or_else(|| error("missing organism name"))?,
+                organism_identifier: self.organism_identifier,
+                gene_name,
+                protein_existence: self
+                    .protein_existence
+                    .ok_or_else(|| error("missing protein existence"))?,
+                sequence_version: self
+                    .sequence_version
+                    .ok_or_else(|| error("missing sequence version"))?,
+            },
+            sequence: self.sequence,
+        })
+    }
+}
+
+fn attribute(element: &BytesStart<'_>, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|a| a.key.local_name().as_ref() == name)
+        .and_then(|a| a.unescape_value().ok().map(|v| v.into_owned()))
+}
+
+// Position in the document, relative to the current entry
+#[derive(Debug, Default)]
+struct State {
+    path: Vec<String>,
+    entry_depth: usize,
+    entry: Option<EntryBuilder>,
+    text: String,
+    name_type: Option<String>,
+}
+
+// Element names from the entry element
+fn relative(path: &[String]) -> Vec<&str> {
+    path.iter().map(String::as_str).collect()
+}
+
+impl State {
+    fn start(&mut self, element: &BytesStart<'_>) {
+        let name = String::from_utf8_lossy(element.local_name().as_ref()).to_string();
+        self.path.push(name);
+        self.text.clear();
+
+        if self.entry.is_none() {
+            if self.path.last().map(String::as_str) == Some("entry") {
+                self.entry_depth = self.path.len() - 1;
+                self.entry = Some(EntryBuilder {
+                    dataset: attribute(element, b"dataset").unwrap_or_default(),
+                    ..EntryBuilder::default()
+                });
+            }
+            return;
+        }
+
+        let relative = relative(&self.path[self.entry_depth..]);
+        let entry = self.entry.as_mut().unwrap();
+        match relative.as_slice() {
+            ["entry", "gene"] => entry.genes += 1,
+            ["entry", "gene", "name"] | ["entry", "organism", "name"] => {
+                self.name_type = attribute(element, b"type")
+            }
+            ["entry", "organism", "dbReference"]
+                if attribute(element, b"type").as_deref() == Some("NCBI Taxonomy") =>
+            {
+                entry.organism_identifier = attribute(element, b"id")
+            }
+            ["entry", "proteinExistence"] => {
+                entry.protein_existence = attribute(element, b"type")
+                    .as_deref()
+                    .and_then(ProteinExistence::from_evidence)
+            }
+            ["entry", "sequence"] => {
+                entry.sequence_version = attribute(element, b"version");
+                entry.fragment = attribute(element, b"fragment");
+            }
+            _ => (),
+        }
+    }
+
+    // Returns the entry when it is complete
+    fn end(&mut self) -> Option<EntryBuilder> {
+        if self.entry.is_none() {
+            self.path.pop();
+            return None;
+        }
+
+        if self.path.len() == self.entry_depth + 1 {
+            self.path.pop();
+            return self.entry.take();
+        }
+
+        let relative = relative(&self.path[self.entry_depth..]);
+        let text = self.text.trim();
+        let entry = self.entry.as_mut().unwrap();
+        match relative.as_slice() {
+            ["entry", "accession"] if entry.accession.is_none() => {
+                entry.accession = Some(text.to_string())
+            }
+            ["entry", "name"] => entry.entry_name = Some(text.to_string()),
+            ["entry", "protein", "recommendedName", "fullName"]
+            | ["entry", "protein", "submittedName", "fullName"]
+                if entry.protein_name.is_none() =>
+            {
+                entry.protein_name = Some(text.to_string())
+            }
+            ["entry", "gene", "name"] if entry.genes == 1 => {
+                if let Some(name_type) = self.name_type.take() {
+                    entry.gene_names.push((name_type, text.to_string()));
+                }
+            }
+            ["entry", "organism", "name"] if self.name_type.as_deref() == Some("scientific") => {
+                entry.organism_name = Some(text.to_string())
+            }
+            ["entry", "sequence"] => {
+                entry.sequence = text.chars().filter(|c| !c.is_whitespace()).collect()
+            }
+            _ => (),
+        }
+        self.path.pop();
+        None
+    }
+}
+
+/// Iterator over the entries of a UniProtKB XML file (`uniprot_sprot.xml`)
+///
+/// The entries are mapped to the fields of the FASTA header, compressed files can be read through a decoder such as `flate2::bufread::GzDecoder`.
+///
+/// ```rust
+/// let xml: &[u8] = br#"<uniprot xmlns="http://uniprot.org/uniprot">
+/// <entry dataset="Swiss-Prot" created="1986-07-21" modified="2020-08-12" version="167">
+///   <accession>P02668</accession>
+///   <accession>P29358</accession>
+///   <name>CASK_BOVIN</name>
+///   <protein><recommendedName><fullName>Kappa-casein</fullName></recommendedName></protein>
+///   <gene><name type="primary">CSN3</name><name type="synonym">CSN10</name></gene>
+///   <organism>
+///     <name type="scientific">Bos taurus</name>
+///     <name type="common">Bovine</name>
+///     <dbReference type="NCBI Taxonomy" id="9913"/>
+///   </organism>
+///   <proteinExistence type="evidence at protein level"/>
+///   <sequence length="10" mass="1160" checksum="0000000000000000" modified="1986-07-21" version="1">MMKSFFLVVT</sequence>
+/// </entry>
+/// </uniprot>"#;
+///
+/// for entry in uniprot_fasta_header::XmlReader::new(xml) {
+///     let entry = entry.unwrap();
+///     assert_eq!(
+///         entry.header.to_string(),
+///         ">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1"
+///     );
+///     assert_eq!(entry.sequence, "MMKSFFLVVT");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct XmlReader<R> {
+    reader: Reader<R>,
+    buffer: Vec<u8>,
+    state: State,
+}
+
+impl<R: BufRead> XmlReader<R> {
+    /// New UniProtKB XML reader
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Reader::from_reader(reader),
+            buffer: Vec::new(),
+            state: State::default(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for XmlReader<R> {
+    type Item = Result<XmlEntry, UniProtHeaderError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.buffer.clear();
+            let event = match self.reader.read_event_into(&mut self.buffer) {
+                Ok(event) => event,
+                Err(e) => return Some(Err(UniProtHeaderError::Xml(e.to_string()))),
+            };
+            match event {
+                Event::Start(element) => self.state.start(&element),
+                Event::Empty(element) => {
+                    self.state.start(&element);
+                    if let Some(entry) = self.state.end() {
+                        return Some(entry.build());
+                    }
+                }
+                Event::Text(text) if self.state.entry.is_some() => match text.unescape() {
+                    Ok(text) => self.state.text.push_str(&text),
+                    Err(e) => return Some(Err(UniProtHeaderError::Xml(e.to_string()))),
+                },
+                Event::End(_) => {
+                    if let Some(entry) = self.state.end() {
+                        return Some(entry.build());
+                    }
+                }
+                Event::Eof if self.state.entry.is_some() => {
+                    self.state.entry = None;
+                    return Some(Err(UniProtHeaderError::Incomplete));
+                }
+                Event::Eof => return None,
+                _ => (),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<uniprot xmlns="http://uniprot.org/uniprot" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+<entry dataset="Swiss-Prot" created="1986-07-21" modified="2020-08-12" version="167">
+  <accession>P02668</accession>
+  <accession>P29358</accession>
+  <name>CASK_BOVIN</name>
+  <protein>
+    <recommendedName>
+      <fullName>Kappa-casein</fullName>
+    </recommendedName>
+    <component>
+      <recommendedName>
+        <fullName>Casoxin-C</fullName>
+      </recommendedName>
+    </component>
+  </protein>
+  <gene>
+    <name type="primary">CSN3</name>
+    <name type="synonym">CSN10</name>
+  </gene>
+  <organism>
+    <name type="scientific">Bos taurus</name>
+    <name type="common">Bovine</name>
+    <dbReference type="NCBI Taxonomy" id="9913"/>
+    <lineage>
+      <taxon>Eukaryota</taxon>
+    </lineage>
+  </organism>
+  <comment type="alternative products">
+    <isoform>
+      <id>P02668-1</id>
+      <name>1</name>
+      <sequence type="displayed"/>
+    </isoform>
+  </comment>
+  <dbReference type="EMBL" id="X14908"/>
+  <proteinExistence type="evidence at protein level"/>
+  <keyword id="KW-0002">3D-structure</keyword>
+  <sequence length="20" mass="2320" checksum="A4A6D6CE4BB5BEB4" modified="1986-07-21" version="1" precursor="true">
+MMKSFFLVVT
+ILALTLPFLG
+</sequence>
+</entry>
+<entry dataset="TrEMBL" created="2014-07-09" modified="2020-08-12" version="20">
+  <accession>A0A023GPI8</accession>
+  <name>A0A023GPI8_CANAL</name>
+  <protein>
+    <submittedName>
+      <fullName evidence="2">Lectin alpha chain</fullName>
+    </submittedName>
+  </protein>
+  <gene>
+    <name type="ORF" evidence="2">ORF1</name>
+    <name type="ordered locus">CLP_0001</name>
+  </gene>
+  <gene>
+    <name type="primary">LEC2</name>
+  </gene>
+  <organism>
+    <name type="scientific">Canavalia lineata</name>
+    <dbReference type="NCBI Taxonomy" id="28957"/>
+  </organism>
+  <proteinExistence type="predicted"/>
+  <sequence length="10" mass="1000" checksum="0000000000000000" modified="2014-07-09" version="1" fragment="single">ADTIVAVELD</sequence>
+</entry>
+<copyright>Copyrighted by the UniProt Consortium</copyright>
+</uniprot>
+"#;
+
+    #[test]
+    fn entries() {
+        let entries: Vec<XmlEntry> = XmlReader::new(XML.as_bytes()).map(Result::unwrap).collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            entries[0].to_record(),
+            Record {
+                header:
+                    ">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1"
+                        .to_string(),
+                sequence: "MMKSFFLVVTILALTLPFLG".to_string(),
+            }
+        );
+        assert_eq!(
+            entries[1].header.to_string(),
+            ">tr|A0A023GPI8|A0A023GPI8_CANAL Lectin alpha chain (Fragment) OS=Canavalia lineata OX=28957 GN=CLP_0001 PE=4 SV=1"
+        );
+    }
+
+    #[test]
+    fn same_as_fasta() {
+        let entry = XmlReader::new(XML.as_bytes()).next().unwrap().unwrap();
+        let fasta = crate::uniprotkb(
+            b">sp|P02668|CASK_BOVIN Kappa-casein OS=Bos taurus OX=9913 GN=CSN3 PE=1 SV=1",
+        )
+        .unwrap();
+        assert_eq!(entry.header, fasta);
+    }
+
+    #[test]
+    fn invalid_entries() {
+        let missing_name = XML.replace("<name>CASK_BOVIN</name>", "");
+        let err = XmlReader::new(missing_name.as_bytes())
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(
+            err,
+            UniProtHeaderError::ParsingError(
+                "P02668".to_string(),
+                "missing entry name".to_string()
+            )
+        );
+
+        let truncated = &XML[..XML.find("<proteinExistence").unwrap()];
+        assert_eq!(
+            XmlReader::new(truncated.as_bytes()).next().unwrap(),
+            Err(UniProtHeaderError::Incomplete)
+        );
+
+        let malformed = XML.replace("</accession>", "</name>");
+        assert!(matches!(
+            XmlReader::new(malformed.as_bytes()).next().unwrap(),
+            Err(UniProtHeaderError::Xml(_))
+        ));
+    }
+}
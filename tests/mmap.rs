@@ -0,0 +1,20 @@
+#![cfg(feature = "memmap2")]
+use elapsed::measure_time;
+
+#[test]
+fn e_coli_mmap() {
+    let fasta = uniprot_fasta_header::MappedFasta::open("tests/assets/E_coli_headers.txt").unwrap();
+    let mut counter: usize = 0;
+
+    let (elapsed, _) = measure_time(|| {
+        for header in fasta.headers() {
+            if let Err(e) = uniprot_fasta_header::uniprotkb(header) {
+                println!("{}", e);
+            }
+            counter += 1;
+        }
+    });
+
+    assert_eq!(counter, 11131);
+    println!("parsed {} headers in = {}", counter, elapsed);
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "rayon")]
+use elapsed::measure_time;
+use std::fs::File;
+
+#[test]
+fn e_coli_par() {
+    let file = File::open("tests/assets/E_coli_headers.txt").unwrap();
+
+    let (elapsed, parsed) = measure_time(|| {
+        uniprot_fasta_header::parse_reader_par(
+            file,
+            uniprot_fasta_header::DEFAULT_CHUNK_SIZE,
+            uniprot_fasta_header::uniprotkb,
+        )
+        .unwrap()
+    });
+
+    let sequential: Vec<_> = std::fs::read_to_string("tests/assets/E_coli_headers.txt")
+        .unwrap()
+        .lines()
+        .map(|line| uniprot_fasta_header::uniprotkb(line.as_bytes()))
+        .collect();
+
+    assert_eq!(parsed.len(), sequential.len());
+    for (par, seq) in parsed.iter().zip(sequential.iter()) {
+        assert_eq!(par.as_ref().ok(), seq.as_ref().ok());
+    }
+    println!("parsed {} headers in = {}", parsed.len(), elapsed);
+}
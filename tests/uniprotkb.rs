@@ -8,16 +8,14 @@ fn e_coli() {
     let mut counter: usize = 0;
 
     let (elapsed, _) = measure_time(|| {
-        for line in io::BufReader::new(file).lines() {
-            if let Ok(header) = line {
-                match uniprot_fasta_header::uniprotkb(header.as_bytes()) {
-                    Ok(_) => {}
-                    Err(e) => {
-                        println!("{}", e);
-                    }
+        for header in io::BufReader::new(file).lines().map_while(Result::ok) {
+            match uniprot_fasta_header::uniprotkb(header.as_bytes()) {
+                Ok(_) => {}
+                Err(e) => {
+                    println!("{}", e);
                 }
-                counter += 1;
             }
+            counter += 1;
         }
     });
     println!("parsed {} headers in = {}", counter, elapsed);